@@ -2,10 +2,13 @@ use std::collections::{HashMap, HashSet};
 use std::io;
 use std::io::Write;
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct Args {
     pub map: HashMap<String, String>,
     pub regex_cmds: Vec<String>,
+    pub sparklines: Vec<String>,
+    pub process_watches: Vec<String>,
+    pub process_expects: Vec<String>,
     pub net_devices: Vec<String>,
     pub whitelist_exact: HashSet<String>,
     pub whitelist_contains: Vec<String>,
@@ -20,6 +23,9 @@ pub struct Args {
 pub fn get_args() -> Args {
     let mut map = HashMap::new();
     let mut regex_cmds = Vec::new();
+    let mut sparklines = Vec::new();
+    let mut process_watches = Vec::new();
+    let mut process_expects = Vec::new();
     let mut net_devices = Vec::new();
 
     let mut w_exact: HashSet<String> = HashSet::new();
@@ -49,6 +55,16 @@ pub fn get_args() -> Args {
                 net_devices.push(back.trim().to_owned());
             }
             map.insert("netdev".into(), back.into());
+        } else if arg == "--netdev-link-info" {
+            map.insert("netdev-link-info".into(), String::new());
+        } else if arg == "--netdev-usage" {
+            map.insert("netdev-usage".into(), String::new());
+        } else if arg.starts_with("--netdev-quota=") {
+            let (_, back) = arg.split_at(15);
+            map.insert("netdev-quota".into(), back.into());
+        } else if arg.starts_with("--netdev-quota-urgent=") {
+            let (_, back) = arg.split_at(22);
+            map.insert("netdev-quota-urgent".into(), back.into());
         } else if arg.starts_with("--netdev_width=") {
             let (_, back) = arg.split_at(15);
             map.insert("netdevwidth".into(), back.into());
@@ -60,14 +76,187 @@ pub fn get_args() -> Args {
         } else if arg.starts_with("--netgraph_size=") {
             let (_, back) = arg.split_at(16);
             map.insert("netgraph-size".into(), back.into());
+        } else if arg.starts_with("--netgraph-colors=") {
+            let (_, back) = arg.split_at(18);
+            map.insert("netgraph-colors".into(), back.into());
+        } else if arg.starts_with("--netgraph-color-mode=") {
+            let (_, back) = arg.split_at(22);
+            map.insert("netgraph-color-mode".into(), back.into());
         } else if arg.starts_with("--interval-sec=") {
             let (_, back) = arg.split_at(15);
             map.insert("interval-sec".into(), back.into());
+        } else if arg.starts_with("--interval-sec-battery=") {
+            let (_, back) = arg.split_at(23);
+            map.insert("interval-sec-battery".into(), back.into());
+        } else if arg.starts_with("--refresh-signal=") {
+            let (_, back) = arg.split_at(17);
+            map.insert("refresh-signal".into(), back.into());
+        } else if arg == "--wireless" {
+            map.insert("wireless".into(), String::new());
+        } else if arg.starts_with("--wireless=") {
+            let (_, back) = arg.split_at(11);
+            map.insert("wireless".into(), back.into());
+        } else if arg.starts_with("--wireless-ssid-cmd=") {
+            let (_, back) = arg.split_at(20);
+            map.insert("wireless-ssid-cmd".into(), back.into());
+        } else if arg == "--vpn" {
+            map.insert("vpn".into(), String::new());
+        } else if arg.starts_with("--vpn=") {
+            let (_, back) = arg.split_at(6);
+            map.insert("vpn".into(), back.into());
+        } else if arg == "--vpn-traffic" {
+            map.insert("vpn-traffic".into(), String::new());
+        } else if arg == "--vpn-expected" {
+            map.insert("vpn-expected".into(), String::new());
+        } else if arg.starts_with("--vpn-warning-color=") {
+            let (_, back) = arg.split_at(20);
+            map.insert("vpn-warning-color".into(), back.into());
+        } else if arg == "--cpu" {
+            map.insert("cpu".into(), String::new());
+        } else if arg == "--cpu-breakdown" {
+            map.insert("cpu-breakdown".into(), String::new());
+        } else if arg == "--cpu-per-core" {
+            map.insert("cpu-per-core".into(), String::new());
+        } else if arg.starts_with("--cpu-thresholds=") {
+            let (_, back) = arg.split_at(17);
+            map.insert("cpu-thresholds".into(), back.into());
+        } else if arg == "--cpufreq" {
+            map.insert("cpufreq".into(), String::new());
+        } else if arg.starts_with("--cpufreq-governors=") {
+            let (_, back) = arg.split_at(20);
+            map.insert("cpufreq-governors".into(), back.into());
+        } else if arg.starts_with("--cpufreq-epps=") {
+            let (_, back) = arg.split_at(15);
+            map.insert("cpufreq-epps".into(), back.into());
+        } else if arg == "--temp" {
+            map.insert("temp".into(), String::new());
+        } else if arg.starts_with("--temp=") {
+            let (_, back) = arg.split_at(7);
+            map.insert("temp".into(), back.into());
+        } else if arg.starts_with("--temp-thresholds=") {
+            let (_, back) = arg.split_at(18);
+            map.insert("temp-thresholds".into(), back.into());
+        } else if arg == "--temp-show-label" {
+            map.insert("temp-show-label".into(), String::new());
+        } else if arg == "--fan" {
+            map.insert("fan".into(), String::new());
+        } else if arg.starts_with("--fan=") {
+            let (_, back) = arg.split_at(6);
+            map.insert("fan".into(), back.into());
+        } else if arg.starts_with("--fan-stall-temp=") {
+            let (_, back) = arg.split_at(17);
+            map.insert("fan-stall-temp".into(), back.into());
+        } else if arg.starts_with("--mem-format=") {
+            let (_, back) = arg.split_at(13);
+            map.insert("mem-format".into(), back.into());
+        } else if arg.starts_with("--mem-thresholds=") {
+            let (_, back) = arg.split_at(17);
+            map.insert("mem-thresholds".into(), back.into());
+        } else if arg.starts_with("--swap-thresholds=") {
+            let (_, back) = arg.split_at(18);
+            map.insert("swap-thresholds".into(), back.into());
+        } else if arg == "--psi" {
+            map.insert("psi".into(), String::new());
+        } else if arg.starts_with("--psi=") {
+            let (_, back) = arg.split_at(6);
+            map.insert("psi".into(), back.into());
+        } else if arg.starts_with("--psi-thresholds=") {
+            let (_, back) = arg.split_at(17);
+            map.insert("psi-thresholds".into(), back.into());
+        } else if arg.starts_with("--psi-sustain=") {
+            let (_, back) = arg.split_at(14);
+            map.insert("psi-sustain".into(), back.into());
+        } else if arg == "--diskio" {
+            map.insert("diskio".into(), String::new());
+        } else if arg.starts_with("--diskio=") {
+            let (_, back) = arg.split_at(9);
+            map.insert("diskio".into(), back.into());
+        } else if arg.starts_with("--diskio-graph=") {
+            let (_, back) = arg.split_at(15);
+            map.insert("diskio-graph".into(), back.into());
+        } else if arg.starts_with("--diskio-graph-colors=") {
+            let (_, back) = arg.split_at(22);
+            map.insert("diskio-graph-colors".into(), back.into());
+        } else if arg.starts_with("--diskio-graph-color-mode=") {
+            let (_, back) = arg.split_at(26);
+            map.insert("diskio-graph-color-mode".into(), back.into());
+        } else if arg.starts_with("--diskio-graph-size=") {
+            let (_, back) = arg.split_at(20);
+            map.insert("diskio-graph-size".into(), back.into());
+        } else if arg == "--fs" {
+            map.insert("fs".into(), String::new());
+        } else if arg.starts_with("--fs=") {
+            let (_, back) = arg.split_at(5);
+            map.insert("fs".into(), back.into());
+        } else if arg.starts_with("--fs-thresholds=") {
+            let (_, back) = arg.split_at(16);
+            map.insert("fs-thresholds".into(), back.into());
+        } else if arg.starts_with("--fs-show-above=") {
+            let (_, back) = arg.split_at(16);
+            map.insert("fs-show-above".into(), back.into());
+        } else if arg == "--mounts" {
+            map.insert("mounts".into(), String::new());
+        } else if arg.starts_with("--mounts-unmount-cmd=") {
+            let (_, back) = arg.split_at(21);
+            map.insert("mounts-unmount-cmd".into(), back.into());
+        } else if arg.starts_with("--process-watch=") {
+            let (_, back) = arg.split_at(16);
+            process_watches.push(back.to_owned());
+        } else if arg.starts_with("--process-expect=") {
+            let (_, back) = arg.split_at(17);
+            process_expects.push(back.to_owned());
+        } else if arg == "--top" {
+            map.insert("top".into(), String::new());
+        } else if arg.starts_with("--top=") {
+            let (_, back) = arg.split_at(6);
+            map.insert("top".into(), back.into());
+        } else if arg == "--privacy" {
+            map.insert("privacy".into(), String::new());
+        } else if arg == "--audio-playback" {
+            map.insert("audio-playback".into(), String::new());
+        } else if arg == "--backlight" {
+            map.insert("backlight".into(), String::new());
+        } else if arg.starts_with("--backlight=") {
+            let (_, back) = arg.split_at(12);
+            map.insert("backlight".into(), back.into());
+        } else if arg.starts_with("--backlight-step=") {
+            let (_, back) = arg.split_at(17);
+            map.insert("backlight-step".into(), back.into());
+        } else if arg.starts_with("--backlight-cmd=") {
+            let (_, back) = arg.split_at(16);
+            map.insert("backlight-cmd".into(), back.into());
+        } else if arg == "--rfkill" {
+            map.insert("rfkill".into(), String::new());
+        } else if arg == "--lock-keys" {
+            map.insert("lock-keys".into(), String::new());
+        } else if arg.starts_with("--lock-keys-interval-ms=") {
+            let (_, back) = arg.split_at(24);
+            map.insert("lock-keys-interval-ms".into(), back.into());
+        } else if arg == "--loadavg-normalize" {
+            map.insert("loadavg-normalize".into(), String::new());
+        } else if arg == "--loadavg-tasks" {
+            map.insert("loadavg-tasks".into(), String::new());
+        } else if arg.starts_with("--loadavg-fields=") {
+            let (_, back) = arg.split_at(17);
+            map.insert("loadavg-fields".into(), back.into());
+        } else if arg.starts_with("--loadavg-thresholds=") {
+            let (_, back) = arg.split_at(21);
+            map.insert("loadavg-thresholds".into(), back.into());
         } else if arg == "--acpi-builtin" {
             map.insert("acpi-builtin".into(), String::new());
+        } else if arg == "--acpi-builtin-plug" {
+            map.insert("acpi-builtin-plug".into(), String::new());
+        } else if arg == "--power" {
+            map.insert("power".into(), String::new());
         } else if arg.starts_with("--regex-cmd=") {
             let (_, back) = arg.split_at(12);
             regex_cmds.push(back.to_owned());
+        } else if arg.starts_with("--sparkline=") {
+            let (_, back) = arg.split_at(12);
+            sparklines.push(back.to_owned());
+        } else if arg.starts_with("--sparkline-colors=") {
+            let (_, back) = arg.split_at(19);
+            map.insert("sparkline-colors".into(), back.into());
         } else if arg.starts_with("--time-format=") {
             let (_, back) = arg.split_at(14);
             map.insert("time-format".into(), back.to_owned());
@@ -108,6 +297,9 @@ pub fn get_args() -> Args {
     Args {
         map,
         regex_cmds,
+        sparklines,
+        process_watches,
+        process_expects,
         net_devices,
         whitelist_exact: w_exact,
         whitelist_contains: w_contains,
@@ -120,6 +312,16 @@ pub fn get_args() -> Args {
     }
 }
 
+/// Splits a command option in the form "<cmd>[SPLIT]<args...>" into the
+/// command and its arguments.
+pub fn parse_cmd(cmd_str: &str) -> (String, Vec<String>) {
+    let mut split_strs = cmd_str.split_terminator("[SPLIT]");
+    let cmd: String = split_strs.next().unwrap_or_default().to_owned();
+    let args: Vec<String> = split_strs.map(|s| s.to_owned()).collect();
+
+    (cmd, args)
+}
+
 pub fn print_usage() {
     let mut stderr_handle = io::stderr().lock();
     stderr_handle.write_all(b"Usage:\n").ok();
@@ -153,6 +355,18 @@ pub fn print_usage() {
     stderr_handle
         .write_all(b"  --blacklist-ends=<str>                           When netdev is \"all\", blacklist netdevs to entries that ends with <str>\n")
         .ok();
+    stderr_handle
+        .write_all(b"  --netdev-link-info                               Show addresses, link speed, and state of the net device (click to cycle)\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --netdev-usage                                   Show today's/this month's traffic of the net device (saved across restarts)\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --netdev-quota=<bytes>                           Set a monthly quota for --netdev-usage (suffixes K/M/G/T allowed)\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --netdev-quota-urgent=<percent>                  Set the percentage of the quota that sets the urgent state (default 90)\n")
+        .ok();
     stderr_handle
         .write_all(b"  --netdev_width=<width>                           Sets the min-width of the netdev output (default 11)\n")
         .ok();
@@ -168,21 +382,198 @@ pub fn print_usage() {
     stderr_handle
         .write_all(b"  --netgraph_dyn_display                           Enable showing the current maximum value in the graph\n")
         .ok();
+    stderr_handle
+        .write_all(b"  --netgraph-colors=<color>,<color>[,<color>...]   Set the color stops (low to high) used to color the net-graph by intensity\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --netgraph-color-mode=<mode>                     Color the net-graph by \"direction\" (default), \"intensity\", or \"both\"\n")
+        .ok();
     stderr_handle
         .write_all(
             b"  --interval-sec=<seconds>                         Output at intervals of <seconds> (default 5)\n",
         )
         .ok();
+    stderr_handle
+        .write_all(b"  --interval-sec-battery=<seconds>                 Output at intervals of <seconds> instead while running on battery\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --refresh-signal=<n>                             Output immediately when receiving the signal SIGRTMIN+<n>\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --wireless[=<device_name>]                       Show link quality, signal level, and SSID of a wireless device\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --wireless-ssid-cmd=<cmd>[SPLIT]<args...>        Get the SSID from the first line of a command's output instead of nl80211\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --vpn[=<pattern>[,<pattern>...]]                 Show VPN/tunnel net devices that are up (default \"wg*,tun*,tailscale*\")\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --vpn-traffic                                    Show the traffic of the VPN net device\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --vpn-expected                                   Show a warning if no VPN net device is up\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --vpn-warning-color=<color>                      Set the color of the --vpn-expected warning (default #ff8888ff)\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --cpu                                            Show CPU usage (from /proc/stat)\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --cpu-breakdown                                  Also show iowait and steal percentages in the CPU usage\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --cpu-per-core                                   Also show a bar for each CPU core in the CPU usage\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --cpu-thresholds=<warning>,<critical>            Color the CPU usage when it reaches the given percentages\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --cpufreq                                        Show CPU frequency, governor, and energy performance preference\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --cpufreq-governors=<governor>[,<governor>...]   Governors to cycle through when left-clicking the CPU frequency\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --cpufreq-epps=<epp>[,<epp>...]                  Energy performance preferences to cycle through when right-clicking\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --temp[=<sensor>[,<sensor>...]]                  Show the highest temperature of the (given) hwmon/thermal zone sensors\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --temp-thresholds=<warning>,<critical>           Color the temperature at the given degrees Celsius (default from sensors)\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --temp-show-label                                Show the label of the sensor with the highest temperature\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --fan[=<fan>[,<fan>...]]                         Show the RPM of (the given) hwmon fans\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --fan-stall-temp=<celsius>                       Flag a fan at 0 RPM when a temperature reaches <celsius> (default 70)\n")
+        .ok();
+    stderr_handle
+        .write_all(
+            b"  --mem-format=<format>                            Set the format of the meminfo block (default \"{used} / {total}\")\n",
+        )
+        .ok();
+    stderr_handle
+        .write_all(b"  --mem-thresholds=<warning>,<critical>            Color the meminfo block when memory usage reaches the given percentages\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --swap-thresholds=<warning>,<critical>           Color the meminfo block when swap usage reaches the given percentages\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --psi[=<resource>[,<resource>...]]               Show pressure stall info of (the given) cpu, memory, and io\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --psi-thresholds=<warning>,<critical>            Color the PSI block at the given \"some\" avg10 percentages (default 10,40)\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --psi-sustain=<updates>                          Only color the PSI block after <updates> updates in a row (default 3)\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --diskio[=<dev>[,<dev>...]]                      Show the disk read/write rates and utilization of (the given) disks\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --diskio-graph=<bytes>|dynamic                   Show a graph of disk I/O, where <bytes> per second is a full block\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --diskio-graph-size=<size>                       Set the number of characters of the disk I/O graph (default 10)\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --diskio-graph-colors=<color>,<color>[,...]       Set the color stops (low to high) used to color the disk I/O graph by intensity\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --diskio-graph-color-mode=<mode>                 Color the disk I/O graph by \"direction\" (default), \"intensity\", or \"both\"\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --fs[=<mount>[,<mount>...]]                      Show the free space of (the given) filesystems (default / and /home)\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --fs-thresholds=<warning>,<critical>             Color the fs block when a filesystem's usage reaches the given percentages (default 80,95)\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --fs-show-above=<percent>                        Only show filesystems with at least <percent> used\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --mounts                                         Show mounted removable and network filesystems\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --mounts-unmount-cmd=<cmd>[SPLIT]<args...>       Run <cmd> with the last mount point when the mounts block is clicked\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --process-watch=<label>[SPLIT]<regex>            Show <label> while a process matching <regex> is running\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --process-expect=<label>[SPLIT]<regex>           Warn with \"NO <label>\" while no process matches <regex>\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --top[=cpu|mem]                                  Show the process using the most CPU (default) or memory\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --privacy                                        Show (as urgent) the processes using a camera or microphone\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --audio-playback                                 Show the sound cards that are playing audio\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --backlight[=<device>]                           Show the backlight brightness, adjusted by scrolling\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --backlight-step=<percent>                       Percentage to change the brightness by per scroll (default 5)\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --backlight-cmd=<cmd>[SPLIT]<args...>            Run <cmd> with the new brightness if writing to sysfs isn't permitted\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --lock-keys                                      Show the Caps Lock, Num Lock, and Scroll Lock keys that are on\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --lock-keys-interval-ms=<ms>                     Check the lock keys every <ms> milliseconds, 0 to disable (default 250)\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --rfkill                                         Show the Wi-Fi, Bluetooth, and WWAN radio states from rfkill\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --loadavg-normalize                              Divide the load averages by the number of online CPUs\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --loadavg-tasks                                  Show the running/total tasks after the load averages\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --loadavg-fields=<field>[,<field>...]            Select which load averages to show (1, 5, 15)\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --loadavg-thresholds=<warning>,<critical>        Color the load average when the 1 minute load per CPU reaches the given values\n")
+        .ok();
     stderr_handle
         .write_all(
             b"  --acpi-builtin                                   Use \"acpi -b\" built-in fetching (battery info, with color)\n",
         )
         .ok();
+    stderr_handle
+        .write_all(b"  --acpi-builtin-plug                              Prefix the battery info with \"AC\" while an AC adapter is plugged in\n")
+        .ok();
+    stderr_handle
+        .write_all(b"  --power                                          Show whether an AC adapter is plugged in and whether the lid is closed\n")
+        .ok();
     stderr_handle
         .write_all(
             b"  --regex-cmd=<cmd>[SPLIT]<args...>[SPLIT]<regex>  Use an output of a command as a metric\n",
         )
         .ok();
+    stderr_handle
+        .write_all(
+            b"  --sparkline=<block>[,<size>[,<max>|dynamic]]     Show a history graph before <block> (cpu, meminfo, loadavg, temperature, regex_cmd_<idx>)\n",
+        )
+        .ok();
+    stderr_handle
+        .write_all(
+            b"  --sparkline-colors=<color>,<color>[,<color>...]  Set the color stops (low to high) used to color sparklines by intensity\n",
+        )
+        .ok();
     stderr_handle
         .write_all(
             b"  --time-format=<date format string>               Set the format string for the date\n",
@@ -0,0 +1,101 @@
+use std::path::Path;
+
+use crate::sysfs::{get_sorted_entries, read_trimmed};
+
+const ASOUND_DIR: &str = "/proc/asound";
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PcmDirection {
+    Playback,
+    Capture,
+}
+
+impl PcmDirection {
+    // PCM directories are named like "pcm0p" (playback) and "pcm0c"
+    // (capture).
+    fn get_suffix(self) -> &'static str {
+        match self {
+            PcmDirection::Playback => "p",
+            PcmDirection::Capture => "c",
+        }
+    }
+}
+
+/// A running PCM substream from /proc/asound.
+#[derive(Debug, Clone)]
+pub struct PcmSubstream {
+    /// The id of the sound card, such as "PCH".
+    pub card: String,
+}
+
+// The status file is "closed" when the substream isn't open, and otherwise
+// has lines of "<key>: <value>", such as "state: RUNNING".
+fn is_running_status(status: &str) -> bool {
+    status.lines().any(|line| {
+        line.split_once(':')
+            .is_some_and(|(key, value)| key.trim() == "state" && value.trim() == "RUNNING")
+    })
+}
+
+/// Returns the substreams of all sound cards in the given direction that are
+/// currently running.
+pub fn get_running_substreams(direction: PcmDirection) -> Vec<PcmSubstream> {
+    let mut substreams: Vec<PcmSubstream> = Vec::new();
+    for card_dir in get_sorted_entries(Path::new(ASOUND_DIR), "card") {
+        let card: String = read_trimmed(&card_dir.join("id")).unwrap_or_else(|| {
+            card_dir
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        for pcm_dir in get_sorted_entries(&card_dir, "pcm") {
+            if !pcm_dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(direction.get_suffix()))
+            {
+                continue;
+            }
+            for sub_dir in get_sorted_entries(&pcm_dir, "sub") {
+                if read_trimmed(&sub_dir.join("status"))
+                    .is_some_and(|status| is_running_status(&status))
+                {
+                    substreams.push(PcmSubstream { card: card.clone() });
+                }
+            }
+        }
+    }
+
+    substreams
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn running_status() {
+        let status = "\
+state: RUNNING
+owner_pid   : 1234
+trigger_time: 5678.901234567
+tstamp      : 0.000000000
+delay       : 1024
+avail       : 0
+avail_max   : 1024
+-----
+hw_ptr      : 123456
+appl_ptr    : 124480";
+        assert!(is_running_status(status));
+    }
+
+    #[test]
+    fn not_running_status() {
+        assert!(!is_running_status("closed"));
+        assert!(!is_running_status(""));
+        assert!(!is_running_status("state: PREPARED\nowner_pid   : 1234"));
+        assert!(!is_running_status("state: SETUP"));
+        // "RUNNING" in another field.
+        assert!(!is_running_status("state: PAUSED\nnote: RUNNING"));
+    }
+}
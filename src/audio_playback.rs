@@ -0,0 +1,23 @@
+use crate::asound::{self, PcmDirection};
+use crate::swaybar_object::SwaybarObject;
+
+/// Shows the sound cards that are currently playing audio.
+#[derive(Debug, Default)]
+pub struct AudioPlaybackInfo;
+
+impl AudioPlaybackInfo {
+    pub fn update(&mut self, object: &mut SwaybarObject) {
+        let mut cards: Vec<String> = Vec::new();
+        for substream in asound::get_running_substreams(PcmDirection::Playback) {
+            if !cards.contains(&substream.card) {
+                cards.push(substream.card);
+            }
+        }
+
+        if cards.is_empty() {
+            object.update_as_hidden();
+        } else {
+            object.update_as_generic(format!("PLAY {}", cards.join(",")), None);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+
+use crate::args;
+use crate::error::Error;
+use crate::swaybar_object::SwaybarObject;
+use crate::sysfs::{get_sorted_entries, read_trimmed};
+
+const BACKLIGHT_DIR: &str = "/sys/class/backlight";
+pub const DEFAULT_STEP: f64 = 5.0;
+
+fn read_value(path: &Path) -> Result<u64, Error> {
+    read_trimmed(path)
+        .ok_or_else(|| Error::from(format!("backlight: failed to read {}", path.display())))?
+        .parse::<u64>()
+        .map_err(|e| Error::from(format!("backlight: invalid {}: {}", path.display(), e)))
+}
+
+pub struct BacklightInfo {
+    device: String,
+    /// The percentage of the maximum brightness to change by on scroll.
+    step: f64,
+    cmd: Option<(String, Vec<String>)>,
+    /// Set once writing to sysfs fails, so that the command is used directly.
+    use_cmd: bool,
+}
+
+impl BacklightInfo {
+    /// `device` is the name of a device in /sys/class/backlight, or empty to
+    /// use the first one.
+    ///
+    /// `cmd` is in the same format as `--regex-cmd` without the regex, and is
+    /// run with the new raw brightness appended when writing to sysfs isn't
+    /// permitted.
+    pub fn new(device: &str, step: f64, cmd: Option<&str>) -> Self {
+        let cmd = cmd.map(args::parse_cmd);
+
+        Self {
+            device: device.to_owned(),
+            step,
+            cmd,
+            use_cmd: false,
+        }
+    }
+
+    fn get_device_dir(&self) -> Result<PathBuf, Error> {
+        if self.device.is_empty() {
+            get_sorted_entries(Path::new(BACKLIGHT_DIR), "")
+                .into_iter()
+                .next()
+                .ok_or_else(|| "backlight: no backlight devices found".to_owned().into())
+        } else {
+            Ok(Path::new(BACKLIGHT_DIR).join(&self.device))
+        }
+    }
+
+    // Returns the device dir, the brightness, and the max brightness.
+    fn read_brightness(&self) -> Result<(PathBuf, u64, u64), Error> {
+        let device_dir = self.get_device_dir()?;
+        let brightness = read_value(&device_dir.join("brightness"))?;
+        let max_brightness = read_value(&device_dir.join("max_brightness"))?;
+
+        Ok((device_dir, brightness, max_brightness))
+    }
+
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        let (_, brightness, max_brightness) = self.read_brightness()?;
+        let percent = if max_brightness == 0 {
+            0.0
+        } else {
+            brightness as f64 / max_brightness as f64 * 100.0
+        };
+        object.update_as_generic(format!("BL {:.0}%", percent), None);
+
+        Ok(())
+    }
+
+    /// Changes the brightness by `steps` times the step percentage, which is
+    /// negative to decrease it.
+    pub fn adjust(&mut self, steps: i32) {
+        let (device_dir, brightness, max_brightness) = match self.read_brightness() {
+            Ok(values) => values,
+            Err(e) => {
+                let mut stderr_handle = io::stderr().lock();
+                stderr_handle
+                    .write_all(format!("WARNING: {}\n", e).as_bytes())
+                    .ok();
+                return;
+            }
+        };
+        // Always change by at least 1 so that small steps on devices with few
+        // levels still do something.
+        let step = (max_brightness as f64 * self.step / 100.0).round().max(1.0) as i64;
+        let new_brightness =
+            (brightness as i64 + step * steps as i64).clamp(0, max_brightness as i64) as u64;
+        if new_brightness == brightness {
+            return;
+        }
+
+        if !self.use_cmd {
+            match fs::write(device_dir.join("brightness"), new_brightness.to_string()) {
+                Ok(()) => return,
+                Err(e) if self.cmd.is_some() && e.kind() == io::ErrorKind::PermissionDenied => {
+                    self.use_cmd = true;
+                }
+                Err(e) => {
+                    let mut stderr_handle = io::stderr().lock();
+                    stderr_handle
+                        .write_all(
+                            format!("WARNING: backlight: Failed to set brightness: {}\n", e)
+                                .as_bytes(),
+                        )
+                        .ok();
+                    return;
+                }
+            }
+        }
+
+        self.run_cmd(new_brightness);
+    }
+
+    fn run_cmd(&self, new_brightness: u64) {
+        let Some((cmd, args)) = self.cmd.as_ref() else {
+            return;
+        };
+
+        let mut cmd_builder = Command::new(cmd);
+        cmd_builder.args(args).arg(new_brightness.to_string());
+        let main_thread = thread::current();
+        // Wait in another thread so that the main loop isn't blocked, and
+        // update the block once the brightness has changed.
+        thread::spawn(move || {
+            let result = cmd_builder.status();
+            let error: Option<String> = match result {
+                Ok(status) if status.success() => None,
+                Ok(status) => Some(status.to_string()),
+                Err(e) => Some(e.to_string()),
+            };
+            if let Some(error) = error {
+                let mut stderr_handle = io::stderr().lock();
+                stderr_handle
+                    .write_all(
+                        format!(
+                            "WARNING: backlight: Failed to run the brightness command: {}\n",
+                            error
+                        )
+                        .as_bytes(),
+                    )
+                    .ok();
+            }
+            main_thread.unpark();
+        });
+    }
+}
@@ -1,5 +1,6 @@
 use std::process::Command;
 
+use crate::color::get_red_green_gradient;
 use crate::error::Error;
 use crate::swaybar_object::SwaybarObject;
 
@@ -9,6 +10,7 @@ use regex::Regex;
 pub struct BattInfo {
     regex: Regex,
     acpi_error: bool,
+    show_plug: bool,
 }
 
 impl Default for BattInfo {
@@ -16,6 +18,7 @@ impl Default for BattInfo {
         Self {
             regex: Regex::new("([0-9]+)%.*").expect("Should be able to compile regex"),
             acpi_error: false,
+            show_plug: false,
         }
     }
 }
@@ -26,7 +29,19 @@ impl BattInfo {
         self.acpi_error
     }
 
-    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+    /// If true, the battery info is prefixed with "AC" while an AC adapter is
+    /// plugged in, like the `--power` block.
+    pub fn set_show_plug(&mut self, show_plug: bool) {
+        self.show_plug = show_plug;
+    }
+
+    /// `ac_online` is from `power::is_ac_online`, and is only used if the "AC"
+    /// prefix is shown.
+    pub fn update(
+        &mut self,
+        ac_online: Option<bool>,
+        object: &mut SwaybarObject,
+    ) -> Result<(), Error> {
         if self.acpi_error {
             return Err(Error::Generic("battinfo: in error state".into()));
         }
@@ -35,19 +50,14 @@ impl BattInfo {
         let string_result = self.get_acpi_string();
         if let Ok(string) = string_result {
             (output_string, output_percentage) = string;
-
-            let percentage: f32 = output_percentage as f32 / 100.0f32;
-            let red: u8 = if percentage > 0.5f32 {
-                (255.0f32 * (1.0f32 - (percentage - 0.5f32) * 2.0f32)) as u8
+            let output_string = if self.show_plug && ac_online == Some(true) {
+                format!("AC {}", output_string)
             } else {
-                255u8
+                output_string
             };
-            let green: u8 = if percentage > 0.5f32 {
-                255u8
-            } else {
-                (255.0f32 * percentage * 2.0f32) as u8
-            };
-            let color: String = format!("#{:02x}{:02x}00ff", red, green);
+
+            let percentage: f32 = output_percentage as f32 / 100.0f32;
+            let color: String = get_red_green_gradient(percentage);
 
             object.update_as_generic(output_string, Some(color));
 
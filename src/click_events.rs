@@ -0,0 +1,57 @@
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, Thread};
+
+use serde::Deserialize;
+
+pub const BUTTON_LEFT: u32 = 1;
+pub const BUTTON_RIGHT: u32 = 3;
+pub const BUTTON_SCROLL_UP: u32 = 4;
+pub const BUTTON_SCROLL_DOWN: u32 = 5;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ClickEvent {
+    pub name: Option<String>,
+    #[allow(dead_code)]
+    pub instance: Option<String>,
+    #[serde(default)]
+    pub button: u32,
+}
+
+/// Spawns a thread that reads click events sent by swaybar over stdin.
+///
+/// The given thread is unparked whenever an event is received so that the
+/// result of the click can be displayed without waiting for the next interval.
+pub fn spawn_reader(main_thread: Thread) -> Receiver<ClickEvent> {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let stdin = io::stdin();
+        for line in stdin.lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            // swaybar sends an infinite JSON array, one element per line.
+            let trimmed = line.trim().trim_start_matches(['[', ',']).trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<ClickEvent>(trimmed) {
+                Ok(event) => {
+                    if sender.send(event).is_err() {
+                        break;
+                    }
+                    main_thread.unpark();
+                }
+                Err(e) => {
+                    let mut stderr_handle = io::stderr().lock();
+                    stderr_handle
+                        .write_all(format!("WARNING: Invalid click event: {}\n", e).as_bytes())
+                        .ok();
+                }
+            }
+        }
+    });
+
+    receiver
+}
@@ -0,0 +1,190 @@
+/// Returns a color going from red (0.0) to yellow (0.5) to green (1.0).
+pub fn get_red_green_gradient(percentage: f32) -> String {
+    let red: u8 = if percentage > 0.5f32 {
+        (255.0f32 * (1.0f32 - (percentage - 0.5f32) * 2.0f32)) as u8
+    } else {
+        255u8
+    };
+    let green: u8 = if percentage > 0.5f32 {
+        255u8
+    } else {
+        (255.0f32 * percentage * 2.0f32) as u8
+    };
+
+    format!("#{:02x}{:02x}00ff", red, green)
+}
+
+fn parse_color(color: &str) -> Option<[u8; 4]> {
+    let hex = color.trim().trim_start_matches('#');
+    // from_str_radix also accepts a leading '+', so check the digits first.
+    if (hex.len() != 6 && hex.len() != 8) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let mut rgba: [u8; 4] = [0, 0, 0, 255];
+    for (idx, channel) in rgba.iter_mut().enumerate().take(hex.len() / 2) {
+        *channel = u8::from_str_radix(&hex[idx * 2..idx * 2 + 2], 16).ok()?;
+    }
+
+    Some(rgba)
+}
+
+fn format_color(rgba: [u8; 4]) -> String {
+    format!(
+        "#{:02x}{:02x}{:02x}{:02x}",
+        rgba[0], rgba[1], rgba[2], rgba[3]
+    )
+}
+
+/// Returns the color halfway between two colors. Invalid colors are returned
+/// as is.
+pub fn blend_colors(first: &str, second: &str) -> String {
+    if let (Some(first), Some(second)) = (parse_color(first), parse_color(second)) {
+        let mut rgba: [u8; 4] = [0; 4];
+        for idx in 0..4 {
+            rgba[idx] = ((first[idx] as u16 + second[idx] as u16) / 2) as u8;
+        }
+        format_color(rgba)
+    } else {
+        first.to_owned()
+    }
+}
+
+/// A list of color stops, where a value from 0.0 to 1.0 is mapped to a color
+/// interpolated between the stops.
+#[derive(Debug, Clone)]
+pub struct ColorRamp {
+    stops: Vec<[u8; 4]>,
+}
+
+impl Default for ColorRamp {
+    // blue -> green -> yellow -> red
+    fn default() -> Self {
+        Self {
+            stops: vec![
+                [0x44, 0x88, 0xff, 0xff],
+                [0x88, 0xff, 0x88, 0xff],
+                [0xff, 0xff, 0x88, 0xff],
+                [0xff, 0x44, 0x44, 0xff],
+            ],
+        }
+    }
+}
+
+impl ColorRamp {
+    /// Parses a comma separated list of colors like "#4488ff,#ff4444".
+    pub fn parse(colors: &str) -> Option<Self> {
+        let stops: Vec<[u8; 4]> = colors
+            .split(',')
+            .map(parse_color)
+            .collect::<Option<Vec<[u8; 4]>>>()?;
+        if stops.is_empty() {
+            None
+        } else {
+            Some(Self { stops })
+        }
+    }
+
+    pub fn get_color(&self, value: f64) -> String {
+        if self.stops.len() == 1 {
+            return format_color(self.stops[0]);
+        }
+
+        let position: f64 = value.clamp(0.0, 1.0) * (self.stops.len() - 1) as f64;
+        let idx: usize = (position.floor() as usize).min(self.stops.len() - 2);
+        let fraction: f64 = position - idx as f64;
+
+        let mut rgba: [u8; 4] = [0; 4];
+        for (channel_idx, channel) in rgba.iter_mut().enumerate() {
+            let from = self.stops[idx][channel_idx] as f64;
+            let to = self.stops[idx + 1][channel_idx] as f64;
+            *channel = (from + (to - from) * fraction).round() as u8;
+        }
+
+        format_color(rgba)
+    }
+}
+
+/// How cells of a graph are colored.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GraphColorMode {
+    /// Colored by the direction of the traffic (download, upload, or both).
+    Direction,
+    /// Colored by a `ColorRamp` based on the height of the cell.
+    Intensity,
+    /// The direction color and the intensity color blended together.
+    Both,
+}
+
+impl GraphColorMode {
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "direction" => Some(GraphColorMode::Direction),
+            "intensity" => Some(GraphColorMode::Intensity),
+            "both" => Some(GraphColorMode::Both),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_color_formats() {
+        assert_eq!(parse_color("#4488ff"), Some([0x44, 0x88, 0xff, 0xff]));
+        assert_eq!(parse_color("4488FF80"), Some([0x44, 0x88, 0xff, 0x80]));
+        assert_eq!(parse_color(" #00000000 "), Some([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn parse_color_invalid() {
+        assert_eq!(parse_color(""), None);
+        assert_eq!(parse_color("#"), None);
+        assert_eq!(parse_color("#fff"), None);
+        assert_eq!(parse_color("#4488ff8"), None);
+        assert_eq!(parse_color("#4488gg"), None);
+        assert_eq!(parse_color("#+f0000"), None);
+        assert_eq!(parse_color("#ääff"), None);
+    }
+
+    #[test]
+    fn color_ramp_parse() {
+        assert!(ColorRamp::parse("").is_none());
+        assert!(ColorRamp::parse("#4488ff,").is_none());
+        assert!(ColorRamp::parse("#4488ff,red").is_none());
+        assert_eq!(ColorRamp::parse("#4488ff").unwrap().stops.len(), 1);
+    }
+
+    #[test]
+    fn color_ramp_get_color() {
+        let ramp = ColorRamp::parse("#000000,#ff8000,#ffffff00").unwrap();
+        assert_eq!(ramp.get_color(0.0), "#000000ff");
+        assert_eq!(ramp.get_color(0.5), "#ff8000ff");
+        assert_eq!(ramp.get_color(1.0), "#ffffff00");
+        // Halfway between 0x00 and 0xff is 127.5, which rounds up to 0x80.
+        assert_eq!(ramp.get_color(0.25), "#804000ff");
+        assert_eq!(ramp.get_color(0.75), "#ffc08080");
+    }
+
+    #[test]
+    fn color_ramp_get_color_clamps() {
+        let ramp = ColorRamp::parse("#000000,#ffffff").unwrap();
+        assert_eq!(ramp.get_color(-1.0), "#000000ff");
+        assert_eq!(ramp.get_color(2.0), "#ffffffff");
+    }
+
+    #[test]
+    fn color_ramp_single_stop() {
+        let ramp = ColorRamp::parse("#4488ff").unwrap();
+        assert_eq!(ramp.get_color(0.0), "#4488ffff");
+        assert_eq!(ramp.get_color(1.0), "#4488ffff");
+    }
+
+    #[test]
+    fn blend_colors_halfway() {
+        assert_eq!(blend_colors("#000000", "#ffffff"), "#7f7f7fff");
+        assert_eq!(blend_colors("invalid", "#ffffff"), "invalid");
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs::File;
+use std::io::Read;
+
+use crate::error::Error;
+use crate::graph::get_graph_char;
+use crate::swaybar_object::SwaybarObject;
+use crate::threshold::{ThresholdLevel, Thresholds};
+
+/// Times (in USER_HZ) from a "cpu" line of /proc/stat.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CpuTimes {
+    user: u64,
+    nice: u64,
+    system: u64,
+    idle: u64,
+    iowait: u64,
+    irq: u64,
+    softirq: u64,
+    steal: u64,
+}
+
+impl CpuTimes {
+    fn parse(line: &str) -> Result<Self, Error> {
+        let entries: Vec<&str> = line.split_whitespace().collect();
+        if entries.len() < 9 {
+            return Err("cpu: failed to parse /proc/stat, cpu line is too short"
+                .to_owned()
+                .into());
+        }
+
+        // "guest" and "guest_nice" are already included in "user" and "nice".
+        Ok(Self {
+            user: entries[1].parse()?,
+            nice: entries[2].parse()?,
+            system: entries[3].parse()?,
+            idle: entries[4].parse()?,
+            iowait: entries[5].parse()?,
+            irq: entries[6].parse()?,
+            softirq: entries[7].parse()?,
+            steal: entries[8].parse()?,
+        })
+    }
+
+    pub fn total(&self) -> u64 {
+        self.user
+            + self.nice
+            + self.system
+            + self.idle
+            + self.iowait
+            + self.irq
+            + self.softirq
+            + self.steal
+    }
+
+    fn idle_total(&self) -> u64 {
+        self.idle + self.iowait
+    }
+}
+
+/// Percentages of time spent between two readings of /proc/stat.
+#[derive(Debug, Copy, Clone, Default)]
+struct CpuUsage {
+    busy: f64,
+    iowait: f64,
+    steal: f64,
+}
+
+impl CpuUsage {
+    fn from_diff(prev: &CpuTimes, current: &CpuTimes) -> Self {
+        let total = current.total().saturating_sub(prev.total());
+        if total == 0 {
+            return Default::default();
+        }
+        let total = total as f64;
+        let idle = current.idle_total().saturating_sub(prev.idle_total()) as f64;
+
+        Self {
+            busy: (total - idle) / total * 100.0,
+            iowait: current.iowait.saturating_sub(prev.iowait) as f64 / total * 100.0,
+            steal: current.steal.saturating_sub(prev.steal) as f64 / total * 100.0,
+        }
+    }
+}
+
+/// Returns the times of all cpus combined, and the id (like "cpu3") and times
+/// of each cpu core that is online.
+pub fn read_stat() -> Result<(CpuTimes, Vec<(String, CpuTimes)>), Error> {
+    let mut stat_string = String::new();
+    {
+        let mut stat_file: File = File::open("/proc/stat")?;
+        stat_file.read_to_string(&mut stat_string)?;
+    }
+
+    let mut all: Option<CpuTimes> = None;
+    let mut cores: Vec<(String, CpuTimes)> = Vec::new();
+    for line in stat_string.lines() {
+        if line.starts_with("cpu ") {
+            all = Some(CpuTimes::parse(line)?);
+        } else if line.starts_with("cpu") {
+            let id = line.split_whitespace().next().unwrap_or_default();
+            cores.push((id.to_owned(), CpuTimes::parse(line)?));
+        } else {
+            break;
+        }
+    }
+
+    Ok((
+        all.ok_or_else(|| Error::from("cpu: failed to parse /proc/stat".to_owned()))?,
+        cores,
+    ))
+}
+
+#[derive(Debug, Default)]
+pub struct CpuInfo {
+    prev_all: CpuTimes,
+    /// Keyed by the id, as offline cores are left out of /proc/stat.
+    prev_cores: HashMap<String, CpuTimes>,
+    show_breakdown: bool,
+    show_per_core: bool,
+    thresholds: Option<Thresholds>,
+    usage: f64,
+}
+
+impl CpuInfo {
+    pub fn new(show_breakdown: bool, show_per_core: bool, thresholds: Option<Thresholds>) -> Self {
+        Self {
+            show_breakdown,
+            show_per_core,
+            thresholds,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the busy percentage from the last update.
+    pub fn get_usage(&self) -> f64 {
+        self.usage
+    }
+
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        let (all, cores) = read_stat()?;
+
+        // On the first update, the previous times are all zero, so the usage
+        // since boot is shown.
+        let usage = CpuUsage::from_diff(&self.prev_all, &all);
+
+        let mut text = format!("CPU {:.0}%", usage.busy);
+        if self.show_breakdown {
+            write!(&mut text, " io {:.0}% st {:.0}%", usage.iowait, usage.steal)?;
+        }
+        if self.show_per_core {
+            text.push(' ');
+            for (id, core) in cores.iter() {
+                let prev_core = self.prev_cores.get(id).copied().unwrap_or_default();
+                let core_usage = CpuUsage::from_diff(&prev_core, core);
+                text.push(get_graph_char((core_usage.busy / 100.0 * 8.0).round() as u8));
+            }
+        }
+
+        let level = self
+            .thresholds
+            .map(|thresholds| thresholds.get_level(usage.busy))
+            .unwrap_or(ThresholdLevel::Normal);
+        object.update_as_threshold(text, level);
+
+        self.prev_all = all;
+        self.prev_cores = cores.into_iter().collect();
+        self.usage = usage.busy;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_cpu_times() {
+        let times = CpuTimes::parse("cpu3 4705 150 1120 16250 520 0 35 12 100 0").unwrap();
+        assert_eq!(times.user, 4705);
+        assert_eq!(times.steal, 12);
+        assert_eq!(times.total(), 4705 + 150 + 1120 + 16250 + 520 + 35 + 12);
+        assert_eq!(times.idle_total(), 16250 + 520);
+    }
+
+    #[test]
+    fn parse_cpu_times_invalid() {
+        assert!(CpuTimes::parse("").is_err());
+        assert!(CpuTimes::parse("cpu 1 2 3 4 5 6 7").is_err());
+        assert!(CpuTimes::parse("cpu 1 2 3 4 5 6 7 -8").is_err());
+        assert!(CpuTimes::parse("cpu 1 2 3 x 5 6 7 8").is_err());
+    }
+
+    #[test]
+    fn usage_from_diff() {
+        let prev = CpuTimes::parse("cpu 100 0 100 700 50 0 0 50").unwrap();
+        let current = CpuTimes::parse("cpu 150 0 150 1000 100 0 0 100").unwrap();
+        let usage = CpuUsage::from_diff(&prev, &current);
+        assert_eq!(usage.busy, 30.0);
+        assert_eq!(usage.iowait, 10.0);
+        assert_eq!(usage.steal, 10.0);
+
+        // The counters didn't change, or went backwards after a core came
+        // back online.
+        let usage = CpuUsage::from_diff(&current, &prev);
+        assert_eq!(usage.busy, 0.0);
+    }
+}
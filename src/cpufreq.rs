@@ -0,0 +1,162 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::error::Error;
+use crate::swaybar_object::SwaybarObject;
+use crate::sysfs::read_trimmed;
+
+const CPU_SYSFS_DIR: &str = "/sys/devices/system/cpu";
+
+pub struct CpuFreqInfo {
+    cpufreq_dirs: Vec<PathBuf>,
+    governors: Vec<String>,
+    epps: Vec<String>,
+}
+
+// Returns the "cpufreq" directory of each cpu that has one.
+fn get_cpufreq_dirs() -> Result<Vec<PathBuf>, Error> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(CPU_SYSFS_DIR)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix("cpu"))
+                .is_some_and(|idx| !idx.is_empty() && idx.chars().all(|c| c.is_ascii_digit()))
+        })
+        .map(|entry| entry.path().join("cpufreq"))
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+
+    Ok(dirs)
+}
+
+// Returns the entry after `current` in `list`, or the first entry if `current`
+// isn't in `list`.
+fn get_next<'a>(list: &'a [String], current: Option<&str>) -> Option<&'a str> {
+    let next_idx = current
+        .and_then(|current| list.iter().position(|entry| entry == current))
+        .map(|idx| (idx + 1) % list.len())
+        .unwrap_or(0);
+
+    list.get(next_idx).map(|s| s.as_str())
+}
+
+impl CpuFreqInfo {
+    /// `governors` and `epps` are comma separated lists to cycle through when
+    /// clicked.
+    pub fn new(governors: Option<&str>, epps: Option<&str>) -> Self {
+        let split_list = |list: Option<&str>| -> Vec<String> {
+            list.map(|list| {
+                list.split(',')
+                    .map(|s| s.trim().to_owned())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+        };
+
+        Self {
+            cpufreq_dirs: Vec::new(),
+            governors: split_list(governors),
+            epps: split_list(epps),
+        }
+    }
+
+    pub fn has_click_actions(&self) -> bool {
+        !self.governors.is_empty() || !self.epps.is_empty()
+    }
+
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        if self.cpufreq_dirs.is_empty() {
+            self.cpufreq_dirs = get_cpufreq_dirs()?;
+            if self.cpufreq_dirs.is_empty() {
+                return Err("cpufreq: no cpufreq directories in sysfs".to_owned().into());
+            }
+        }
+
+        let mut freq_sum: u64 = 0;
+        let mut freq_max: u64 = 0;
+        let mut freq_count: u64 = 0;
+        for dir in &self.cpufreq_dirs {
+            // Offline cpus fail to read, so skip them.
+            if let Some(freq) = read_trimmed(&dir.join("scaling_cur_freq"))
+                .and_then(|freq| freq.parse::<u64>().ok())
+            {
+                freq_sum += freq;
+                freq_max = freq_max.max(freq);
+                freq_count += 1;
+            }
+        }
+        if freq_count == 0 {
+            return Err("cpufreq: failed to read scaling_cur_freq".to_owned().into());
+        }
+
+        // Frequencies are in kHz.
+        let mut text = format!(
+            "{:.2} GHz max {:.2} GHz",
+            freq_sum as f64 / freq_count as f64 / 1_000_000.0,
+            freq_max as f64 / 1_000_000.0
+        );
+        if let Some(governor) = self.get_governor() {
+            text.push(' ');
+            text.push_str(&governor);
+        }
+        if let Some(epp) = self.get_epp() {
+            text.push(' ');
+            text.push_str(&epp);
+        }
+
+        object.update_as_generic(text, None);
+
+        Ok(())
+    }
+
+    fn get_governor(&self) -> Option<String> {
+        read_trimmed(&self.cpufreq_dirs.first()?.join("scaling_governor"))
+    }
+
+    fn get_epp(&self) -> Option<String> {
+        read_trimmed(
+            &self
+                .cpufreq_dirs
+                .first()?
+                .join("energy_performance_preference"),
+        )
+    }
+
+    pub fn cycle_governor(&mut self) {
+        let current = self.get_governor();
+        if let Some(next) = get_next(&self.governors, current.as_deref()) {
+            self.write_all_cpus("scaling_governor", next);
+        }
+    }
+
+    pub fn cycle_epp(&mut self) {
+        let current = self.get_epp();
+        if let Some(next) = get_next(&self.epps, current.as_deref()) {
+            self.write_all_cpus("energy_performance_preference", next);
+        }
+    }
+
+    fn write_all_cpus(&self, file: &str, value: &str) {
+        for dir in &self.cpufreq_dirs {
+            if let Err(e) = fs::write(dir.join(file), value) {
+                let mut stderr_handle = io::stderr().lock();
+                stderr_handle
+                    .write_all(
+                        format!(
+                            "WARNING: cpufreq: Failed to set {} to \"{}\": {}\n",
+                            file, value, e
+                        )
+                        .as_bytes(),
+                    )
+                    .ok();
+                // Don't repeat the same error for every cpu.
+                break;
+            }
+        }
+    }
+}
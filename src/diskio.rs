@@ -0,0 +1,164 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::time::Instant;
+
+use crate::error::Error;
+use crate::graph::{Graph, GraphItemType};
+use crate::swaybar_object::SwaybarObject;
+use crate::units::format_bytes;
+
+const SYS_BLOCK_DIR: &str = "/sys/block";
+// /proc/diskstats counts sectors of 512 bytes, regardless of the device.
+const SECTOR_SIZE: u64 = 512;
+
+/// Counters from a line of /proc/diskstats.
+#[derive(Debug, Copy, Clone, Default)]
+struct DiskStats {
+    sectors_read: u64,
+    sectors_written: u64,
+    /// Milliseconds spent doing I/O.
+    io_ticks: u64,
+}
+
+// Returns the block devices backed by hardware, which excludes loop, ram,
+// zram, and device mapper devices. Partitions are not listed in /sys/block.
+fn get_physical_disks() -> Result<Vec<String>, Error> {
+    let mut disks: Vec<String> = fs::read_dir(SYS_BLOCK_DIR)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("device").exists())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    disks.sort();
+
+    Ok(disks)
+}
+
+fn read_diskstats(devices: &[String]) -> Result<Vec<(String, DiskStats)>, Error> {
+    let mut diskstats_string = String::new();
+    {
+        let mut diskstats_file: File = File::open("/proc/diskstats")?;
+        diskstats_file.read_to_string(&mut diskstats_string)?;
+    }
+
+    let mut stats: Vec<(String, DiskStats)> = Vec::new();
+    for line in diskstats_string.lines() {
+        let entries: Vec<&str> = line.split_whitespace().collect();
+        if entries.len() < 13 || !devices.iter().any(|dev| dev == entries[2]) {
+            continue;
+        }
+        stats.push((
+            entries[2].to_owned(),
+            DiskStats {
+                sectors_read: entries[5].parse()?,
+                sectors_written: entries[9].parse()?,
+                io_ticks: entries[12].parse()?,
+            },
+        ));
+    }
+
+    Ok(stats)
+}
+
+pub struct DiskIoInfo {
+    devices: Vec<String>,
+    prev_stats: Vec<(String, DiskStats)>,
+    prev_instant: Option<Instant>,
+    graph: Option<Graph>,
+    graph_max: Option<f64>,
+}
+
+impl DiskIoInfo {
+    /// `devices` is a comma separated list of block devices (such as "sda").
+    /// If empty, all physical disks are used.
+    ///
+    /// If `graph_size` is set, a graph of the larger of read or write bytes
+    /// per second is kept, scaled by `graph_max` (or dynamically if `None`).
+    pub fn new(devices: &str, graph_size: Option<usize>, graph_max: Option<f64>) -> Self {
+        Self {
+            devices: devices
+                .split(',')
+                .map(|s| s.trim().to_owned())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            prev_stats: Vec::new(),
+            prev_instant: None,
+            graph: graph_size.map(|size| Graph::new(size, GraphItemType::Both)),
+            graph_max,
+        }
+    }
+
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        if self.devices.is_empty() {
+            self.devices = get_physical_disks()?;
+            if self.devices.is_empty() {
+                return Err("diskio: no physical disks in /sys/block".to_owned().into());
+            }
+        }
+
+        let stats = read_diskstats(&self.devices)?;
+        if stats.is_empty() {
+            return Err("diskio: no matching devices in /proc/diskstats"
+                .to_owned()
+                .into());
+        }
+        let now = Instant::now();
+
+        let mut read_bytes: u64 = 0;
+        let mut written_bytes: u64 = 0;
+        let mut max_io_ticks: u64 = 0;
+        for (dev_name, current) in &stats {
+            let Some((_, prev)) = self.prev_stats.iter().find(|(name, _)| name == dev_name) else {
+                continue;
+            };
+            read_bytes += current.sectors_read.saturating_sub(prev.sectors_read) * SECTOR_SIZE;
+            written_bytes +=
+                current.sectors_written.saturating_sub(prev.sectors_written) * SECTOR_SIZE;
+            max_io_ticks = max_io_ticks.max(current.io_ticks.saturating_sub(prev.io_ticks));
+        }
+
+        let elapsed: f64 = self
+            .prev_instant
+            .map(|prev| now.duration_since(prev).as_secs_f64())
+            .unwrap_or(0.0);
+        let (read_rate, write_rate, util): (f64, f64, f64) = if elapsed > 0.0 {
+            (
+                read_bytes as f64 / elapsed,
+                written_bytes as f64 / elapsed,
+                // The utilization of the busiest disk.
+                (max_io_ticks as f64 / (elapsed * 1000.0) * 100.0).min(100.0),
+            )
+        } else {
+            (0.0, 0.0, 0.0)
+        };
+
+        let text = format!(
+            "R {}/s W {}/s {:.0}%",
+            format_bytes(read_rate),
+            format_bytes(write_rate),
+            util
+        );
+        object.update_as_generic(text, None);
+
+        if let Some(graph) = self.graph.as_mut() {
+            // "Download" and "Upload" are used for read and write, so that the
+            // graph is colored like the net graph.
+            let graph_type = if read_rate > write_rate {
+                GraphItemType::Download
+            } else if write_rate > read_rate {
+                GraphItemType::Upload
+            } else {
+                GraphItemType::Both
+            };
+            graph.push(read_rate.max(write_rate), graph_type, self.graph_max);
+        }
+
+        self.prev_stats = stats;
+        self.prev_instant = Some(now);
+
+        Ok(())
+    }
+
+    pub fn get_graph(&self) -> Option<&Graph> {
+        self.graph.as_ref()
+    }
+}
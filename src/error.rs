@@ -3,6 +3,7 @@ pub enum Error {
     Generic(String),
     Regex(regex::Error),
     ParseInt(std::num::ParseIntError),
+    ParseFloat(std::num::ParseFloatError),
     IO(std::io::Error),
     FromUTF8(std::string::FromUtf8Error),
     Format(std::fmt::Error),
@@ -26,6 +27,12 @@ impl From<std::num::ParseIntError> for Error {
     }
 }
 
+impl From<std::num::ParseFloatError> for Error {
+    fn from(error: std::num::ParseFloatError) -> Self {
+        Error::ParseFloat(error)
+    }
+}
+
 impl From<std::io::Error> for Error {
     fn from(error: std::io::Error) -> Self {
         Error::IO(error)
@@ -50,6 +57,7 @@ impl std::fmt::Display for Error {
             Error::Generic(s) => f.write_str(s),
             Error::Regex(e) => e.fmt(f),
             Error::ParseInt(e) => e.fmt(f),
+            Error::ParseFloat(e) => e.fmt(f),
             Error::IO(e) => e.fmt(f),
             Error::FromUTF8(e) => e.fmt(f),
             Error::Format(e) => e.fmt(f),
@@ -63,6 +71,7 @@ impl std::error::Error for Error {
             Error::Generic(_) => None,
             Error::Regex(e) => e.source(),
             Error::ParseInt(e) => e.source(),
+            Error::ParseFloat(e) => e.source(),
             Error::IO(e) => e.source(),
             Error::FromUTF8(e) => e.source(),
             Error::Format(e) => e.source(),
@@ -10,7 +10,7 @@ pub struct ExternalRegexResult {
 
 pub fn get_cmd_output(
     cmd: &str,
-    args: &[&str],
+    args: &[String],
     regex: &Regex,
 ) -> Result<ExternalRegexResult, Error> {
     let mut cmd_builder = Command::new(cmd);
@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+use crate::swaybar_object::SwaybarObject;
+use crate::sysfs::{get_sorted_entries, read_trimmed};
+use crate::thermal::{self, HWMON_DIR, TempSensor};
+use crate::threshold::ThresholdLevel;
+
+const DEFAULT_STALL_TEMP: f64 = 70.0;
+
+struct FanSensor {
+    chip: String,
+    label: String,
+    input_path: PathBuf,
+}
+
+impl FanSensor {
+    fn matches(&self, pattern: &str) -> bool {
+        self.label == pattern || self.chip == pattern
+    }
+
+    fn read(&self) -> Option<u64> {
+        read_trimmed(&self.input_path)?.parse().ok()
+    }
+}
+
+fn discover_fans() -> Vec<FanSensor> {
+    let mut fans: Vec<FanSensor> = Vec::new();
+
+    for hwmon_dir in get_sorted_entries(Path::new(HWMON_DIR), "hwmon") {
+        let chip = read_trimmed(&hwmon_dir.join("name")).unwrap_or_default();
+        for input_path in get_sorted_entries(&hwmon_dir, "fan") {
+            let Some(prefix) = input_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix("_input"))
+                .map(|prefix| prefix.to_owned())
+            else {
+                continue;
+            };
+            let label = read_trimmed(&hwmon_dir.join(format!("{}_label", prefix)))
+                .unwrap_or_else(|| format!("{} {}", chip, prefix));
+            fans.push(FanSensor {
+                chip: chip.clone(),
+                label,
+                input_path,
+            });
+        }
+    }
+
+    fans
+}
+
+pub struct FanInfo {
+    fans: Vec<FanSensor>,
+    patterns: Vec<String>,
+    temp_sensors: Vec<TempSensor>,
+    stall_temp: f64,
+}
+
+impl FanInfo {
+    /// `patterns` is a comma separated list of fan labels (or hwmon device
+    /// names) to show. If empty, all fans are shown.
+    ///
+    /// A fan at 0 RPM is flagged when any sensor matching `temp_patterns` (or
+    /// any sensor if empty) is at or above `stall_temp` degrees Celsius.
+    pub fn new(patterns: &str, temp_patterns: &str, stall_temp: Option<f64>) -> Self {
+        let split_patterns = |patterns: &str| -> Vec<String> {
+            patterns
+                .split(',')
+                .map(|s| s.trim().to_owned())
+                .filter(|s| !s.is_empty())
+                .collect()
+        };
+        let temp_patterns = split_patterns(temp_patterns);
+
+        Self {
+            fans: Vec::new(),
+            patterns: split_patterns(patterns),
+            temp_sensors: thermal::discover_sensors()
+                .into_iter()
+                .filter(|sensor| {
+                    temp_patterns.is_empty()
+                        || temp_patterns.iter().any(|pattern| sensor.matches(pattern))
+                })
+                .collect(),
+            stall_temp: stall_temp.unwrap_or(DEFAULT_STALL_TEMP),
+        }
+    }
+
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        if self.fans.is_empty() {
+            self.fans = discover_fans()
+                .into_iter()
+                .filter(|fan| {
+                    self.patterns.is_empty()
+                        || self.patterns.iter().any(|pattern| fan.matches(pattern))
+                })
+                .collect();
+            if self.fans.is_empty() {
+                return Err("fan: no matching fans found".to_owned().into());
+            }
+        }
+
+        let mut rpms: Vec<u64> = Vec::new();
+        for fan in &self.fans {
+            if let Some(rpm) = fan.read() {
+                rpms.push(rpm);
+            }
+        }
+        if rpms.is_empty() {
+            return Err("fan: failed to read fans".to_owned().into());
+        }
+
+        let rpm_strings: Vec<String> = rpms.iter().map(|rpm| rpm.to_string()).collect();
+        let mut text: String = format!("{} RPM", rpm_strings.join(" "));
+        let mut level = ThresholdLevel::Normal;
+
+        if rpms.contains(&0) {
+            let is_hot = self
+                .temp_sensors
+                .iter()
+                .filter_map(|sensor| sensor.read())
+                .any(|temp| temp >= self.stall_temp);
+            if is_hot {
+                text.push_str(" STALLED");
+                level = ThresholdLevel::Critical;
+            }
+        }
+
+        object.update_as_threshold(text, level);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use std::ffi::CString;
+use std::fmt::Write;
+use std::fs;
+use std::mem::MaybeUninit;
+use std::os::unix::fs::MetadataExt;
+
+use crate::error::Error;
+use crate::swaybar_object::SwaybarObject;
+use crate::threshold::{ThresholdLevel, Thresholds};
+use crate::units::format_bytes;
+
+const DEFAULT_MOUNTS: [&str; 2] = ["/", "/home"];
+const DEFAULT_THRESHOLDS: Thresholds = Thresholds {
+    warning: 80.0,
+    critical: 95.0,
+};
+
+#[derive(Debug, Copy, Clone)]
+struct FsStats {
+    /// The device of the filesystem.
+    dev: u64,
+    used: u64,
+    available: u64,
+}
+
+impl FsStats {
+    // Like df, reserved blocks are counted as neither used nor available.
+    fn percent(&self) -> f64 {
+        let total = self.used + self.available;
+        if total == 0 {
+            0.0
+        } else {
+            self.used as f64 / total as f64 * 100.0
+        }
+    }
+}
+
+fn statvfs(mount: &str) -> Result<FsStats, Error> {
+    let path = CString::new(mount)
+        .map_err(|_| Error::from(format!("fs: invalid mount point \"{}\"", mount)))?;
+    let mut stat: MaybeUninit<libc::statvfs> = MaybeUninit::uninit();
+    if unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return Err(format!(
+            "fs: statvfs failed on \"{}\": {}",
+            mount,
+            std::io::Error::last_os_error()
+        )
+        .into());
+    }
+    let stat = unsafe { stat.assume_init() };
+
+    Ok(FsStats {
+        dev: fs::metadata(mount)?.dev(),
+        used: stat.f_blocks.saturating_sub(stat.f_bfree) * stat.f_frsize,
+        available: stat.f_bavail * stat.f_frsize,
+    })
+}
+
+pub struct FsUsageInfo {
+    mounts: Vec<String>,
+    thresholds: Thresholds,
+    show_above: Option<f64>,
+}
+
+impl FsUsageInfo {
+    /// `mounts` is a comma separated list of mount points. If empty, "/" and
+    /// "/home" are used.
+    ///
+    /// If `thresholds` is None, the block is colored at 80% and 95% used.
+    ///
+    /// If `show_above` is set, only filesystems with at least that percentage
+    /// used are shown, and the block is hidden if there are none.
+    pub fn new(mounts: &str, thresholds: Option<Thresholds>, show_above: Option<f64>) -> Self {
+        let mut mounts: Vec<String> = mounts
+            .split(',')
+            .map(|s| s.trim().to_owned())
+            .filter(|s| !s.is_empty())
+            .collect();
+        if mounts.is_empty() {
+            mounts = DEFAULT_MOUNTS.iter().map(|s| s.to_string()).collect();
+        }
+
+        Self {
+            mounts,
+            thresholds: thresholds.unwrap_or(DEFAULT_THRESHOLDS),
+            show_above,
+        }
+    }
+
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        let mut text = String::new();
+        let mut level = ThresholdLevel::Normal;
+        let mut seen_devs: Vec<u64> = Vec::new();
+        for mount in &self.mounts {
+            let stats = match statvfs(mount) {
+                Ok(stats) => stats,
+                // The default mount points may not exist.
+                Err(_) if DEFAULT_MOUNTS.contains(&mount.as_str()) => continue,
+                // Other mount points may be missing, such as an unplugged
+                // disk, which shouldn't stop the other filesystems from being
+                // shown.
+                Err(_) => {
+                    if self.show_above.is_none() {
+                        if !text.is_empty() {
+                            text.push(' ');
+                        }
+                        write!(&mut text, "{} unavailable", mount)?;
+                    }
+                    continue;
+                }
+            };
+            // Skip mount points on an already shown filesystem, such as
+            // "/home" when it isn't a separate partition.
+            if seen_devs.contains(&stats.dev) {
+                continue;
+            }
+            seen_devs.push(stats.dev);
+
+            let percent = stats.percent();
+            if self
+                .show_above
+                .is_some_and(|show_above| percent < show_above)
+            {
+                continue;
+            }
+            let fs_level = self.thresholds.get_level(percent);
+            if fs_level > level {
+                level = fs_level;
+            }
+
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            write!(
+                &mut text,
+                "{} {} free {:.0}%",
+                mount,
+                format_bytes(stats.available as f64),
+                percent
+            )?;
+        }
+
+        if text.is_empty() {
+            object.update_as_hidden();
+        } else {
+            object.update_as_threshold(text, level);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+use std::fmt::Write as FMTWrite;
+use std::io::{self, Write};
+
+use crate::color::{ColorRamp, GraphColorMode, blend_colors};
+use crate::error::Error;
+use crate::swaybar_object::{SwaybarArray, SwaybarObject};
+
+const DEFAULT_GRAPH_SIZE: usize = 10;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GraphItemType {
+    Download,
+    Upload,
+    Both,
+    /// A graph of a single value, which has no direction.
+    Value,
+}
+
+impl GraphItemType {
+    pub fn get_color(self) -> Option<&'static str> {
+        match self {
+            GraphItemType::Download => Some("#ff8888ff"),
+            GraphItemType::Upload => Some("#88ff88ff"),
+            GraphItemType::Both => Some("#ffff88ff"),
+            GraphItemType::Value => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GraphItem {
+    value: char,
+    level: u8,
+    num_value: f64,
+    value_type: GraphItemType,
+}
+
+// Returns a space or one of the 8 unicode block characters, based on level
+// (0 to 8).
+pub fn get_graph_char(level: u8) -> char {
+    match level {
+        0 => ' ',
+        1 => '▁',
+        2 => '▂',
+        3 => '▃',
+        4 => '▄',
+        5 => '▅',
+        6 => '▆',
+        7 => '▇',
+        _ => '█',
+    }
+}
+
+impl GraphItem {
+    pub fn get_value(&self) -> char {
+        self.value
+    }
+
+    /// Returns the height of the graph item from 0 to 8.
+    pub fn get_level(&self) -> u8 {
+        self.level
+    }
+
+    pub fn set_level(&mut self, level: u8) {
+        self.level = level.min(8);
+        self.value = get_graph_char(self.level);
+    }
+
+    pub fn get_num_value(&self) -> f64 {
+        self.num_value
+    }
+
+    pub fn get_value_type(&self) -> GraphItemType {
+        self.value_type
+    }
+}
+
+/// A history of values displayed with unicode block characters, where the
+/// right side is the most recent.
+#[derive(Debug, Clone)]
+pub struct Graph {
+    items: Vec<GraphItem>,
+    history_max: f64,
+    history_max_idx: usize,
+}
+
+impl Graph {
+    /// `value_type` is the type of the initial (empty) items.
+    pub fn new(size: usize, value_type: GraphItemType) -> Self {
+        Self {
+            items: vec![
+                GraphItem {
+                    value: ' ',
+                    level: 0,
+                    num_value: 0.0,
+                    value_type,
+                };
+                size.max(1)
+            ],
+            history_max: 0.0,
+            history_max_idx: 0,
+        }
+    }
+
+    /// Adds a value to the graph, removing the oldest value.
+    ///
+    /// If `max_opt` is `None`, the graph is scaled dynamically by the maximum
+    /// value in its history.
+    pub fn push(&mut self, value: f64, value_type: GraphItemType, max_opt: Option<f64>) {
+        self.items.rotate_left(1);
+        let end_idx = self.items.len() - 1;
+        self.items[end_idx] = GraphItem {
+            value: ' ',
+            level: 0,
+            num_value: value,
+            value_type,
+        };
+
+        if let Some(max) = max_opt {
+            let level: u8 = if value > max {
+                8
+            } else {
+                (value / max * 8.0f64).round() as u8
+            };
+            self.items[end_idx].set_level(level);
+        } else {
+            self.history_max = 0.0;
+            self.history_max_idx = 0;
+            for (idx, value) in self
+                .items
+                .iter()
+                .map(|item| item.get_num_value())
+                .enumerate()
+            {
+                if self.history_max < value {
+                    self.history_max = value;
+                    self.history_max_idx = idx;
+                }
+            }
+
+            for item in self.items.iter_mut() {
+                if self.history_max > 0.0 {
+                    item.set_level((8.0 * item.get_num_value() / self.history_max).round() as u8);
+                } else {
+                    item.set_level(0);
+                }
+            }
+        }
+    }
+
+    pub fn get_items(&self) -> &Vec<GraphItem> {
+        &self.items
+    }
+
+    /// Returns the maximum value in the history and its index, only updated
+    /// when the graph is scaled dynamically.
+    pub fn get_history_max(&self) -> (f64, usize) {
+        (self.history_max, self.history_max_idx)
+    }
+
+    /// Returns the graph as pango markup, where each character is colored
+    /// based on `color_mode`.
+    pub fn to_pango_string(
+        &self,
+        color_mode: GraphColorMode,
+        color_ramp: &ColorRamp,
+    ) -> Result<String, Error> {
+        let mut text = String::new();
+        for item in self.items.iter() {
+            let direction_color: Option<&str> = item.get_value_type().get_color();
+            let intensity: f64 = item.get_level() as f64 / 8.0;
+            let item_color: Option<String> = match color_mode {
+                GraphColorMode::Direction => direction_color.map(|color| color.to_owned()),
+                GraphColorMode::Intensity => Some(color_ramp.get_color(intensity)),
+                GraphColorMode::Both => Some(if let Some(color) = direction_color {
+                    blend_colors(color, &color_ramp.get_color(intensity))
+                } else {
+                    color_ramp.get_color(intensity)
+                }),
+            };
+            if let Some(item_color) = item_color {
+                write!(
+                    &mut text,
+                    "<span color=\"{}\">{}</span>",
+                    item_color,
+                    item.get_value()
+                )?;
+            } else {
+                text.push(item.get_value());
+            }
+        }
+
+        Ok(text)
+    }
+}
+
+/// Returns the first number (e.g. "42" or "-1.5") found in `text`.
+pub fn get_first_number(text: &str) -> Option<f64> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    // Include a leading minus sign.
+    let start = if text[..start].ends_with('-') {
+        start - 1
+    } else {
+        start
+    };
+    // Stop at a second '.', such as in "1.2.3".
+    let mut seen_dot = false;
+    let end = text[start + 1..]
+        .find(|c: char| {
+            if c == '.' && !seen_dot {
+                seen_dot = true;
+                false
+            } else {
+                !c.is_ascii_digit()
+            }
+        })
+        .map(|end| start + 1 + end)
+        .unwrap_or(text.len());
+
+    text[start..end].trim_end_matches('.').parse::<f64>().ok()
+}
+
+struct Sparkline {
+    graph: Graph,
+    max: Option<f64>,
+}
+
+/// Graphs of values from blocks other than the net block, each displayed in
+/// an object named "<block>_graph" placed before the block.
+pub struct Sparklines {
+    sparklines: HashMap<String, Sparkline>,
+    color_mode: GraphColorMode,
+    color_ramp: ColorRamp,
+}
+
+impl Sparklines {
+    /// Each spec is "<block>[,<size>[,<max>]]", where `<max>` is a number or
+    /// "dynamic". If `<max>` is omitted, it defaults to `default_max(block)`.
+    pub fn new(
+        specs: &[String],
+        color_ramp: Option<ColorRamp>,
+        default_max: impl Fn(&str) -> Option<f64>,
+    ) -> Self {
+        let mut sparklines: HashMap<String, Sparkline> = HashMap::new();
+        for spec in specs {
+            let mut parts = spec.split(',').map(|s| s.trim());
+            let block: &str = parts.next().unwrap_or_default();
+            let mut is_valid: bool = !block.is_empty();
+
+            let mut size: usize = DEFAULT_GRAPH_SIZE;
+            if let Some(size_str) = parts.next() {
+                match size_str.parse::<usize>() {
+                    Ok(parsed) if parsed > 0 => size = parsed,
+                    _ => is_valid = false,
+                }
+            }
+
+            let mut max: Option<f64> = default_max(block);
+            if let Some(max_str) = parts.next() {
+                if max_str == "dynamic" {
+                    max = None;
+                } else {
+                    match max_str.parse::<f64>() {
+                        Ok(parsed) if parsed > 0.0 => max = Some(parsed),
+                        _ => is_valid = false,
+                    }
+                }
+            }
+
+            if !is_valid {
+                let mut stderr_handle = io::stderr().lock();
+                stderr_handle
+                    .write_all(
+                        format!(
+                            "WARNING: Invalid value passed to --sparkline=\"{}\", ignoring...\n",
+                            spec
+                        )
+                        .as_bytes(),
+                    )
+                    .ok();
+                continue;
+            }
+
+            sparklines.insert(
+                block.to_owned(),
+                Sparkline {
+                    graph: Graph::new(size, GraphItemType::Value),
+                    max,
+                },
+            );
+        }
+
+        Self {
+            sparklines,
+            color_mode: if color_ramp.is_some() {
+                GraphColorMode::Intensity
+            } else {
+                GraphColorMode::Direction
+            },
+            color_ramp: color_ramp.unwrap_or_default(),
+        }
+    }
+
+    /// Pushes the graph object of `block` if it has a sparkline. Should be
+    /// called right before pushing the block's object.
+    pub fn push_object(&self, block: &str, array: &mut SwaybarArray) {
+        if let Some(sparkline) = self.sparklines.get(block) {
+            let mut graph_obj = SwaybarObject::from_string(
+                format!("{}_graph", block),
+                " ".repeat(sparkline.graph.get_items().len()),
+            );
+            graph_obj.markup = Some("pango".to_owned());
+            array.push_object(graph_obj);
+        }
+    }
+
+    /// Adds a value to the sparkline of `block` (if it has one). If `value` is
+    /// `None`, the graph is left as is.
+    pub fn update(
+        &mut self,
+        block: &str,
+        value: Option<f64>,
+        array: &mut SwaybarArray,
+    ) -> Result<(), Error> {
+        let (Some(sparkline), Some(value)) = (self.sparklines.get_mut(block), value) else {
+            return Ok(());
+        };
+
+        sparkline
+            .graph
+            .push(value, GraphItemType::Value, sparkline.max);
+        if let Some(graph_obj) = array.get_by_name_mut(&format!("{}_graph", block)) {
+            graph_obj.full_text = sparkline
+                .graph
+                .to_pango_string(self.color_mode, &self.color_ramp)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_number_in_text() {
+        assert_eq!(get_first_number("42"), Some(42.0));
+        assert_eq!(get_first_number("CPU 45%"), Some(45.0));
+        assert_eq!(get_first_number("load 0.52 0.48 0.40"), Some(0.52));
+        assert_eq!(get_first_number("temp: 3."), Some(3.0));
+        assert_eq!(get_first_number("v1.2.3"), Some(1.2));
+    }
+
+    #[test]
+    fn first_number_negative() {
+        assert_eq!(get_first_number("-1.5 dBm"), Some(-1.5));
+        assert_eq!(get_first_number("signal -67 dBm"), Some(-67.0));
+        assert_eq!(get_first_number("5-3"), Some(5.0));
+        assert_eq!(get_first_number("--3"), Some(-3.0));
+    }
+
+    #[test]
+    fn first_number_missing() {
+        assert_eq!(get_first_number(""), None);
+        assert_eq!(get_first_number("no numbers"), None);
+        assert_eq!(get_first_number("-"), None);
+        assert_eq!(get_first_number("."), None);
+    }
+}
@@ -0,0 +1,127 @@
+use std::fmt::Write;
+use std::fs::File;
+use std::io::{self, Read, Write as IOWrite};
+
+use crate::error::Error;
+use crate::swaybar_object::SwaybarObject;
+use crate::threshold::{ThresholdLevel, Thresholds};
+
+/// Returns the number of online cpus.
+pub fn get_online_cpus() -> u64 {
+    let count = unsafe { libc::sysconf(libc::_SC_NPROCESSORS_ONLN) };
+    if count < 1 { 1 } else { count as u64 }
+}
+
+pub struct LoadAvgInfo {
+    /// Indices (0 to 2) of the 1, 5, and 15 minute averages to show.
+    fields: Vec<usize>,
+    normalize: bool,
+    show_tasks: bool,
+    thresholds: Option<Thresholds>,
+    load: Option<f64>,
+}
+
+impl LoadAvgInfo {
+    /// `fields` is a comma separated list of "1", "5", and "15". If empty, all
+    /// averages are shown.
+    ///
+    /// `thresholds` are compared to the 1 minute average divided by the number
+    /// of online cpus.
+    pub fn new(
+        fields: &str,
+        normalize: bool,
+        show_tasks: bool,
+        thresholds: Option<Thresholds>,
+    ) -> Self {
+        let mut field_indices: Vec<usize> = Vec::new();
+        for field in fields
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+        {
+            match field {
+                "1" => field_indices.push(0),
+                "5" => field_indices.push(1),
+                "15" => field_indices.push(2),
+                _ => {
+                    let mut stderr_handle = io::stderr().lock();
+                    stderr_handle
+                        .write_all(
+                            format!(
+                                "WARNING: Invalid field \"{}\" passed to --loadavg-fields=..., ignoring...\n",
+                                field
+                            )
+                            .as_bytes(),
+                        )
+                        .ok();
+                }
+            }
+        }
+        if field_indices.is_empty() {
+            field_indices = vec![0, 1, 2];
+        }
+
+        Self {
+            fields: field_indices,
+            normalize,
+            show_tasks,
+            thresholds,
+            load: None,
+        }
+    }
+
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        self.load = None;
+
+        let mut loadavg_string = String::new();
+        {
+            let mut loadavg_file: File = File::open("/proc/loadavg")?;
+            loadavg_file.read_to_string(&mut loadavg_string)?;
+        }
+
+        let loadavg_parts: Vec<&str> = loadavg_string.split_whitespace().collect();
+        if loadavg_parts.len() < 4 {
+            return Err("loadavg: failed to parse".to_owned().into());
+        }
+        let cpus = get_online_cpus() as f64;
+
+        let mut text = String::new();
+        for idx in &self.fields {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            if self.normalize {
+                let load: f64 = loadavg_parts[*idx].parse()?;
+                write!(&mut text, "{:.2}", load / cpus)?;
+            } else {
+                text.push_str(loadavg_parts[*idx]);
+            }
+        }
+        if self.show_tasks {
+            // The 4th field is "<running>/<total>" tasks.
+            text.push(' ');
+            text.push_str(loadavg_parts[3]);
+        }
+
+        let load_per_core: f64 = loadavg_parts[0].parse::<f64>()? / cpus;
+        let level = self
+            .thresholds
+            .map(|thresholds| thresholds.get_level(load_per_core))
+            .unwrap_or(ThresholdLevel::Normal);
+        object.update_as_threshold(text, level);
+
+        let first_load: f64 = loadavg_parts[self.fields[0]].parse()?;
+        self.load = Some(if self.normalize {
+            first_load / cpus
+        } else {
+            first_load
+        });
+
+        Ok(())
+    }
+
+    /// Returns the first shown average from the last successful update.
+    pub fn get_load(&self) -> Option<f64> {
+        self.load
+    }
+}
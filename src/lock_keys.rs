@@ -0,0 +1,95 @@
+use std::path::Path;
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::swaybar_object::SwaybarObject;
+use crate::sysfs::{get_sorted_entries, read_trimmed};
+
+const LEDS_DIR: &str = "/sys/class/leds";
+pub const DEFAULT_POLL_INTERVAL_MS: u64 = 250;
+
+// The LED name suffixes and the labels shown while they are on.
+const LOCK_KEYS: [(&str, &str); 3] = [
+    ("::capslock", "CAPS"),
+    ("::numlock", "NUM"),
+    ("::scrolllock", "SCRL"),
+];
+
+// Each keyboard has its own LEDs, such as "input3::capslock", so a lock key is
+// considered on if any of them are on.
+fn read_lock_keys() -> [bool; 3] {
+    let mut states = [false; 3];
+    for led_dir in get_sorted_entries(Path::new(LEDS_DIR), "") {
+        let name = led_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        for ((suffix, _), state) in LOCK_KEYS.iter().zip(states.iter_mut()) {
+            if !*state
+                && name.ends_with(suffix)
+                && read_trimmed(&led_dir.join("brightness")).is_some_and(|b| b != "0")
+            {
+                *state = true;
+            }
+        }
+    }
+
+    states
+}
+
+/// Spawns a thread that checks the LEDs every `interval` and unparks
+/// `main_thread` when they change, as sysfs LEDs can't be polled for changes.
+fn spawn_watcher(interval: Duration, main_thread: Thread) {
+    thread::spawn(move || {
+        let mut prev_states = read_lock_keys();
+        loop {
+            thread::sleep(interval);
+            let states = read_lock_keys();
+            if states != prev_states {
+                prev_states = states;
+                main_thread.unpark();
+            }
+        }
+    });
+}
+
+pub struct LockKeysInfo {
+    poll_interval: Option<Duration>,
+    watcher_spawned: bool,
+}
+
+impl LockKeysInfo {
+    /// If `poll_interval` is None, the LEDs are only checked on the regular
+    /// interval (or the refresh signal).
+    pub fn new(poll_interval: Option<Duration>) -> Self {
+        Self {
+            poll_interval,
+            watcher_spawned: false,
+        }
+    }
+
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        if !self.watcher_spawned {
+            if let Some(interval) = self.poll_interval {
+                spawn_watcher(interval, thread::current());
+            }
+            self.watcher_spawned = true;
+        }
+
+        let labels: Vec<&str> = LOCK_KEYS
+            .iter()
+            .zip(read_lock_keys())
+            .filter(|(_, state)| *state)
+            .map(|((_, label), _)| *label)
+            .collect();
+
+        if labels.is_empty() {
+            object.update_as_hidden();
+        } else {
+            object.update_as_generic(labels.join(" "), None);
+        }
+
+        Ok(())
+    }
+}
@@ -1,20 +1,49 @@
 mod args;
+mod asound;
+mod audio_playback;
+mod backlight;
 mod builtin;
+mod click_events;
+mod color;
+mod cpu;
+mod cpufreq;
+mod diskio;
 mod error;
 mod external;
+mod fan;
+mod fs_usage;
+mod graph;
+mod loadavg;
+mod lock_keys;
+mod mem;
+mod mounts;
+mod net_link;
+mod nl80211;
+mod power;
+mod privacy;
 mod proc;
+mod processes;
+mod psi;
+mod rfkill;
 mod signal_handling;
 mod swaybar_object;
+mod sysfs;
+mod thermal;
+mod threshold;
+mod top;
+mod units;
+mod usage;
+mod vpn;
+mod wireless;
 
 use error::Error;
 
 use std::ffi::c_int;
-use std::fmt::Write as FMTWrite;
 use std::io::{self, Write};
 use std::sync::RwLock;
 use std::sync::atomic::AtomicBool;
 use std::thread::{self, Thread};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use swaybar_object::*;
 
 const DEFAULT_FMT_STRING: &str = "%F %r";
@@ -32,6 +61,128 @@ extern "C" fn handle_signal(_sig: c_int) {
     }
 }
 
+extern "C" fn handle_refresh_signal(_sig: c_int) {
+    if let Ok(t_handle_opt) = MAIN_THREAD_HANDLE.read().as_ref()
+        && let Some(t_handle) = t_handle_opt.as_ref()
+    {
+        t_handle.unpark();
+    }
+}
+
+/// Updates the block named `name` with `update`, or pushes it as a new block
+/// if `is_empty`. If `update` fails, the block shows `error_text`, and `error`
+/// (if given) is set so that the block isn't updated again.
+fn update_block(
+    array: &mut SwaybarArray,
+    is_empty: bool,
+    name: &str,
+    error_text: &str,
+    error: Option<&mut bool>,
+    update: impl FnOnce(&mut SwaybarObject) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let mut new_object: Option<SwaybarObject> = None;
+    let obj: &mut SwaybarObject = if is_empty {
+        new_object.insert(SwaybarObject::new(name.to_owned()))
+    } else if let Some(obj) = array.get_by_name_mut(name)
+        && !error.as_deref().is_some_and(|error| *error)
+    {
+        obj
+    } else {
+        return Ok(());
+    };
+
+    if let Err(e) = update(obj) {
+        obj.update_as_error(error_text.to_owned());
+        if let Some(error) = error {
+            *error = true;
+        }
+        let mut stderr_handle = io::stderr().lock();
+        stderr_handle.write_all(format!("{}\n", e).as_bytes())?;
+    }
+    if let Some(new_object) = new_object {
+        array.push_object(new_object);
+    }
+
+    Ok(())
+}
+
+/// Parks the main thread until the next tick after `last_tick`. Returns false
+/// if it was unparked before then, such as by a click event.
+///
+/// Ticks are every `battery_interval` while `ac_online` is Some(false), and
+/// every `interval` otherwise. While on battery, `ac_online` is still checked
+/// every `interval`, so that plugging in goes back to `interval` right away.
+fn wait_for_tick(
+    last_tick: Instant,
+    interval: Duration,
+    battery_interval: Option<Duration>,
+    ac_online: &mut Option<bool>,
+) -> bool {
+    loop {
+        let tick_interval = match battery_interval {
+            Some(battery_interval) if *ac_online == Some(false) => battery_interval,
+            _ => interval,
+        };
+        let elapsed = last_tick.elapsed();
+        if elapsed >= tick_interval {
+            return true;
+        }
+        // The next multiple of `interval` since the last tick.
+        let next_check = interval * (elapsed.as_nanos() / interval.as_nanos() + 1) as u32;
+        let wake_at = tick_interval.min(next_check);
+        thread::park_timeout(wake_at - elapsed);
+        if last_tick.elapsed() < wake_at {
+            return false;
+        }
+        if wake_at < tick_interval {
+            *ac_online = power::is_ac_online();
+        }
+    }
+}
+
+/// Gets the color mode and the color ramp of a graph from the args with the
+/// given keys, such as "netgraph-colors" and "netgraph-color-mode". Setting the
+/// colors also enables the intensity mode unless the mode is given.
+fn get_graph_colors(
+    args_result: &args::Args,
+    colors_key: &str,
+    mode_key: &str,
+) -> Result<(color::GraphColorMode, color::ColorRamp), Error> {
+    let mut color_mode = color::GraphColorMode::Direction;
+    let mut color_ramp: color::ColorRamp = Default::default();
+    if let Some(colors) = args_result.map.get(colors_key) {
+        if let Some(ramp) = color::ColorRamp::parse(colors) {
+            color_ramp = ramp;
+            color_mode = color::GraphColorMode::Intensity;
+        } else {
+            let mut stderr_handle = io::stderr().lock();
+            stderr_handle.write_all(
+                format!(
+                    "WARNING: Invalid value passed to --{}=..., ignoring...\n",
+                    colors_key
+                )
+                .as_bytes(),
+            )?;
+        }
+    }
+    if let Some(mode_str) = args_result.map.get(mode_key) {
+        if let Some(mode) = color::GraphColorMode::parse(mode_str) {
+            color_mode = mode;
+        } else {
+            let mut stderr_handle = io::stderr().lock();
+            stderr_handle.write_all(
+                format!(
+                    "WARNING: Invalid value passed to --{}=..., ignoring...\n",
+                    mode_key
+                )
+                .as_bytes(),
+            )?;
+        }
+    }
+
+    Ok((color_mode, color_ramp))
+}
+
 fn main() -> Result<(), Error> {
     let args_result = args::get_args();
     if args_result.map.contains_key("help") {
@@ -39,28 +190,14 @@ fn main() -> Result<(), Error> {
         return Ok(());
     }
 
-    let mut cmds: Vec<(&str, Vec<&str>, regex::Regex)> = Vec::new();
+    let mut cmds: Vec<(String, Vec<String>, regex::Regex)> = Vec::new();
     for regex_cmd in &args_result.regex_cmds {
-        let mut split_strs = regex_cmd.split_terminator("[SPLIT]");
-        let cmd: &str = split_strs.next().expect("Should have cmd in option");
-        let mut args: Vec<&str> = Vec::new();
-        let mut next: Option<&str>;
-        loop {
-            next = split_strs.next();
-            if let Some(str) = next {
-                args.push(str);
-            } else {
-                break;
-            }
-        }
-        if args.is_empty() {
+        let (cmd, mut args) = args::parse_cmd(regex_cmd);
+        let Some(regex_str) = args.pop() else {
             panic!("Missing regex for --regex-cmd=<cmd>,<args...>,<regex>");
-        }
+        };
 
-        let regex_str: &str = args[args.len() - 1];
-        args.pop();
-
-        let regex = regex::Regex::new(regex_str).expect("Should be able to compile regex");
+        let regex = regex::Regex::new(&regex_str).expect("Should be able to compile regex");
 
         cmds.push((cmd, args, regex));
     }
@@ -137,6 +274,26 @@ fn main() -> Result<(), Error> {
     if args_result.map.contains_key("netgraph-dyndisplay") {
         net_graph_show_dynamic_max = true;
     }
+    let (net_graph_color_mode, net_graph_color_ramp) =
+        get_graph_colors(&args_result, "netgraph-colors", "netgraph-color-mode")?;
+    let mut sparkline_color_ramp: Option<color::ColorRamp> = None;
+    if let Some(colors) = args_result.map.get("sparkline-colors") {
+        sparkline_color_ramp = color::ColorRamp::parse(colors);
+        if sparkline_color_ramp.is_none() {
+            let mut stderr_handle = io::stderr().lock();
+            stderr_handle.write_all(
+                "WARNING: Invalid value passed to --sparkline-colors=..., ignoring...\n".as_bytes(),
+            )?;
+        }
+    }
+    let mut sparklines = graph::Sparklines::new(
+        &args_result.sparklines,
+        sparkline_color_ramp,
+        |block| match block {
+            "cpu" | "meminfo" => Some(100.0),
+            _ => None,
+        },
+    );
     if args_result.map.contains_key("interval-sec") {
         let seconds: Result<i64, _> = args_result.map.get("interval-sec").unwrap().parse();
         if let Ok(seconds_value) = seconds {
@@ -159,19 +316,372 @@ fn main() -> Result<(), Error> {
         }
     }
 
+    let mut battery_interval: Option<Duration> = None;
+    if let Some(seconds_str) = args_result.map.get("interval-sec-battery") {
+        if let Ok(seconds) = seconds_str.parse::<u64>()
+            && seconds > 0
+        {
+            battery_interval = Some(Duration::from_secs(seconds));
+        } else {
+            let mut stderr_handle = io::stderr().lock();
+            stderr_handle.write_all(
+                "WARNING: Invalid value passed to --interval-sec-battery=..., ignoring...\n"
+                    .as_bytes(),
+            )?;
+        }
+    }
+
     let mut batt_info: builtin::BattInfo = Default::default();
+    batt_info.set_show_plug(args_result.map.contains_key("acpi-builtin-plug"));
     let batt_info_enabled: bool = args_result.map.contains_key("acpi-builtin");
     let mut batt_info_error: bool = false;
 
+    let mut data_usage: Option<usage::DataUsage> = None;
+    if net_obj.is_some() && args_result.map.contains_key("netdev-usage") {
+        let mut quota: Option<u64> = None;
+        if let Some(quota_str) = args_result.map.get("netdev-quota") {
+            quota = units::parse_bytes(quota_str);
+            if quota.is_none() {
+                let mut stderr_handle = io::stderr().lock();
+                stderr_handle.write_all(
+                    "WARNING: Invalid value passed to --netdev-quota=..., ignoring...\n".as_bytes(),
+                )?;
+            }
+        }
+        let mut quota_urgent_percent: Option<f64> = None;
+        if let Some(percent_str) = args_result.map.get("netdev-quota-urgent") {
+            if let Ok(percent) = percent_str.parse::<f64>() {
+                quota_urgent_percent = Some(percent);
+            } else {
+                let mut stderr_handle = io::stderr().lock();
+                stderr_handle.write_all(
+                    "WARNING: Invalid value passed to --netdev-quota-urgent=..., ignoring...\n"
+                        .as_bytes(),
+                )?;
+            }
+        }
+        // With "all", traffic through virtual devices would be counted twice.
+        let skip_virtual: bool = net_obj
+            .as_ref()
+            .is_some_and(|net| net.get_dev_name() == "all");
+        data_usage = Some(usage::DataUsage::new(
+            quota,
+            quota_urgent_percent,
+            skip_virtual,
+        ));
+    }
+
+    let mut wireless_info: Option<wireless::WirelessInfo> = None;
+    let mut wireless_info_error: bool = false;
+    if let Some(dev_name) = args_result.map.get("wireless") {
+        wireless_info = Some(wireless::WirelessInfo::new(
+            if dev_name.is_empty() {
+                None
+            } else {
+                Some(dev_name.to_owned())
+            },
+            args_result.map.get("wireless-ssid-cmd").map(|s| s.as_str()),
+        ));
+    }
+
+    let mut vpn_info: Option<vpn::VpnInfo> = None;
+    let mut vpn_info_error: bool = false;
+    if let Some(patterns) = args_result.map.get("vpn") {
+        vpn_info = Some(vpn::VpnInfo::new(patterns, &args_result));
+    }
+
+    let mut cpu_info: Option<cpu::CpuInfo> = None;
+    let mut cpu_info_error: bool = false;
+    if args_result.map.contains_key("cpu") {
+        cpu_info = Some(cpu::CpuInfo::new(
+            args_result.map.contains_key("cpu-breakdown"),
+            args_result.map.contains_key("cpu-per-core"),
+            threshold::get_thresholds_arg(&args_result, "cpu-thresholds"),
+        ));
+    }
+
+    let mut cpufreq_info: Option<cpufreq::CpuFreqInfo> = None;
+    let mut cpufreq_info_error: bool = false;
+    if args_result.map.contains_key("cpufreq") {
+        cpufreq_info = Some(cpufreq::CpuFreqInfo::new(
+            args_result.map.get("cpufreq-governors").map(|s| s.as_str()),
+            args_result.map.get("cpufreq-epps").map(|s| s.as_str()),
+        ));
+    }
+
+    let mut thermal_info: Option<thermal::ThermalInfo> = None;
+    let mut thermal_info_error: bool = false;
+    if let Some(patterns) = args_result.map.get("temp") {
+        thermal_info = Some(thermal::ThermalInfo::new(
+            patterns,
+            threshold::get_thresholds_arg(&args_result, "temp-thresholds"),
+            args_result.map.contains_key("temp-show-label"),
+        ));
+    }
+
+    let mut fan_info: Option<fan::FanInfo> = None;
+    let mut fan_info_error: bool = false;
+    if let Some(patterns) = args_result.map.get("fan") {
+        let mut stall_temp: Option<f64> = None;
+        if let Some(temp_str) = args_result.map.get("fan-stall-temp") {
+            if let Ok(temp) = temp_str.parse::<f64>() {
+                stall_temp = Some(temp);
+            } else {
+                let mut stderr_handle = io::stderr().lock();
+                stderr_handle.write_all(
+                    "WARNING: Invalid value passed to --fan-stall-temp=..., ignoring...\n"
+                        .as_bytes(),
+                )?;
+            }
+        }
+        fan_info = Some(fan::FanInfo::new(
+            patterns,
+            args_result
+                .map
+                .get("temp")
+                .map(|s| s.as_str())
+                .unwrap_or(""),
+            stall_temp,
+        ));
+    }
+
+    let mut mem_info = mem::MemInfo::new(
+        args_result.map.get("mem-format").map(|s| s.as_str()),
+        threshold::get_thresholds_arg(&args_result, "mem-thresholds"),
+        threshold::get_thresholds_arg(&args_result, "swap-thresholds"),
+    );
+
+    let mut loadavg_info = loadavg::LoadAvgInfo::new(
+        args_result
+            .map
+            .get("loadavg-fields")
+            .map(|s| s.as_str())
+            .unwrap_or(""),
+        args_result.map.contains_key("loadavg-normalize"),
+        args_result.map.contains_key("loadavg-tasks"),
+        threshold::get_thresholds_arg(&args_result, "loadavg-thresholds"),
+    );
+
+    let mut diskio_info: Option<diskio::DiskIoInfo> = None;
+    let mut diskio_info_error: bool = false;
+    let (diskio_graph_color_mode, diskio_graph_color_ramp) = get_graph_colors(
+        &args_result,
+        "diskio-graph-colors",
+        "diskio-graph-color-mode",
+    )?;
+    if let Some(devices) = args_result.map.get("diskio") {
+        let mut graph_size: Option<usize> = None;
+        let mut graph_max: Option<f64> = None;
+        if let Some(max_str) = args_result.map.get("diskio-graph") {
+            graph_size = Some(10);
+            if max_str != "dynamic" {
+                if let Some(max) = units::parse_bytes(max_str)
+                    && max > 0
+                {
+                    graph_max = Some(max as f64);
+                } else {
+                    graph_size = None;
+                    let mut stderr_handle = io::stderr().lock();
+                    stderr_handle.write_all(
+                        "WARNING: Invalid value passed to --diskio-graph=..., ignoring...\n"
+                            .as_bytes(),
+                    )?;
+                }
+            }
+        }
+        if graph_size.is_some()
+            && let Some(size_str) = args_result.map.get("diskio-graph-size")
+        {
+            if let Ok(size) = size_str.parse::<usize>()
+                && size > 0
+            {
+                graph_size = Some(size);
+            } else {
+                let mut stderr_handle = io::stderr().lock();
+                stderr_handle.write_all(
+                    "WARNING: Invalid value passed to --diskio-graph-size=..., ignoring...\n"
+                        .as_bytes(),
+                )?;
+            }
+        }
+        diskio_info = Some(diskio::DiskIoInfo::new(devices, graph_size, graph_max));
+    }
+
+    let mut fs_usage_info: Option<fs_usage::FsUsageInfo> = None;
+    let mut fs_usage_info_error: bool = false;
+    if let Some(mounts) = args_result.map.get("fs") {
+        let mut show_above: Option<f64> = None;
+        if let Some(percent_str) = args_result.map.get("fs-show-above") {
+            if let Ok(percent) = percent_str.parse::<f64>() {
+                show_above = Some(percent);
+            } else {
+                let mut stderr_handle = io::stderr().lock();
+                stderr_handle.write_all(
+                    "WARNING: Invalid value passed to --fs-show-above=..., ignoring...\n"
+                        .as_bytes(),
+                )?;
+            }
+        }
+        fs_usage_info = Some(fs_usage::FsUsageInfo::new(
+            mounts,
+            threshold::get_thresholds_arg(&args_result, "fs-thresholds"),
+            show_above,
+        ));
+    }
+
+    let mut mounts_info: Option<mounts::MountsInfo> = None;
+    let mut mounts_info_error: bool = false;
+    if args_result.map.contains_key("mounts") {
+        mounts_info = Some(mounts::MountsInfo::new(
+            args_result
+                .map
+                .get("mounts-unmount-cmd")
+                .map(|s| s.as_str()),
+        ));
+    }
+
+    let mut processes_info: Option<processes::ProcessesInfo> = None;
+    let mut processes_info_error: bool = false;
+    if !args_result.process_watches.is_empty() || !args_result.process_expects.is_empty() {
+        let info = processes::ProcessesInfo::new(
+            &args_result.process_watches,
+            &args_result.process_expects,
+        );
+        if !info.is_empty() {
+            processes_info = Some(info);
+        }
+    }
+
+    let mut top_info: Option<top::TopInfo> = None;
+    let mut top_info_error: bool = false;
+    if let Some(mode_str) = args_result.map.get("top") {
+        if mode_str.is_empty() {
+            top_info = Some(top::TopInfo::new(top::TopMode::Cpu));
+        } else if let Some(mode) = top::TopMode::parse(mode_str) {
+            top_info = Some(top::TopInfo::new(mode));
+        } else {
+            let mut stderr_handle = io::stderr().lock();
+            stderr_handle.write_all(
+                "WARNING: Invalid value passed to --top=..., ignoring...\n".as_bytes(),
+            )?;
+        }
+    }
+
+    let mut privacy_info: Option<privacy::PrivacyInfo> = None;
+    let mut privacy_info_error: bool = false;
+    if args_result.map.contains_key("privacy") {
+        privacy_info = Some(Default::default());
+    }
+
+    let mut audio_playback_info: Option<audio_playback::AudioPlaybackInfo> = None;
+    if args_result.map.contains_key("audio-playback") {
+        audio_playback_info = Some(Default::default());
+    }
+
+    let mut backlight_info: Option<backlight::BacklightInfo> = None;
+    let mut backlight_info_error: bool = false;
+    if let Some(device) = args_result.map.get("backlight") {
+        let mut step: f64 = backlight::DEFAULT_STEP;
+        if let Some(step_str) = args_result.map.get("backlight-step") {
+            if let Ok(parsed) = step_str.parse::<f64>()
+                && parsed > 0.0
+            {
+                step = parsed;
+            } else {
+                let mut stderr_handle = io::stderr().lock();
+                stderr_handle.write_all(
+                    "WARNING: Invalid value passed to --backlight-step=..., ignoring...\n"
+                        .as_bytes(),
+                )?;
+            }
+        }
+        backlight_info = Some(backlight::BacklightInfo::new(
+            device,
+            step,
+            args_result.map.get("backlight-cmd").map(|s| s.as_str()),
+        ));
+    }
+
+    let mut lock_keys_info: Option<lock_keys::LockKeysInfo> = None;
+    let mut lock_keys_info_error: bool = false;
+    if args_result.map.contains_key("lock-keys") {
+        let mut poll_ms: u64 = lock_keys::DEFAULT_POLL_INTERVAL_MS;
+        if let Some(ms_str) = args_result.map.get("lock-keys-interval-ms") {
+            if let Ok(ms) = ms_str.parse::<u64>() {
+                poll_ms = ms;
+            } else {
+                let mut stderr_handle = io::stderr().lock();
+                stderr_handle.write_all(
+                    "WARNING: Invalid value passed to --lock-keys-interval-ms=..., ignoring...\n"
+                        .as_bytes(),
+                )?;
+            }
+        }
+        lock_keys_info = Some(lock_keys::LockKeysInfo::new(
+            (poll_ms > 0).then(|| Duration::from_millis(poll_ms)),
+        ));
+    }
+
+    let mut rfkill_info: Option<rfkill::RfkillInfo> = None;
+    let mut rfkill_info_error: bool = false;
+    if args_result.map.contains_key("rfkill") {
+        rfkill_info = Some(Default::default());
+    }
+
+    let mut power_info: Option<power::PowerInfo> = None;
+    let mut power_info_error: bool = false;
+    if args_result.map.contains_key("power") {
+        power_info = Some(Default::default());
+    }
+
+    let mut psi_info: Option<psi::PsiInfo> = None;
+    let mut psi_info_error: bool = false;
+    if let Some(resources) = args_result.map.get("psi") {
+        let mut sustain_count: Option<u32> = None;
+        if let Some(count_str) = args_result.map.get("psi-sustain") {
+            if let Ok(count) = count_str.parse::<u32>() {
+                sustain_count = Some(count);
+            } else {
+                let mut stderr_handle = io::stderr().lock();
+                stderr_handle.write_all(
+                    "WARNING: Invalid value passed to --psi-sustain=..., ignoring...\n".as_bytes(),
+                )?;
+            }
+        }
+        psi_info = Some(psi::PsiInfo::new(
+            resources,
+            threshold::get_thresholds_arg(&args_result, "psi-thresholds"),
+            sustain_count,
+        ));
+    }
+
+    let mut net_link_info: Option<net_link::NetLinkInfo> = None;
+    if net_obj.is_some() && args_result.map.contains_key("netdev-link-info") {
+        net_link_info = Some(Default::default());
+    }
+
+    let click_events_enabled: bool = net_link_info.is_some()
+        || cpufreq_info
+            .as_ref()
+            .is_some_and(|cpufreq| cpufreq.has_click_actions())
+        || mounts_info
+            .as_ref()
+            .is_some_and(|mounts| mounts.has_click_actions())
+        || backlight_info.is_some()
+        || rfkill_info.is_some();
+
     let mut time_fmt_str = DEFAULT_FMT_STRING;
     if let Some(s) = args_result.map.get("time-format") {
         time_fmt_str = s;
     }
 
+    let mut header = swaybar_object::SwaybarHeader::new();
+    if click_events_enabled {
+        header.click_events = Some(true);
+    }
     println!(
         "{}",
-        serde_json::to_string(&swaybar_object::SwaybarHeader::new())
-            .expect("Should be able to serialize SwaybarHeader")
+        serde_json::to_string(&header).expect("Should be able to serialize SwaybarHeader")
     );
     println!("[");
 
@@ -259,8 +769,8 @@ fn main() -> Result<(), Error> {
                 net.reset_fresh();
             }
         }
-        let (netinfo_string, graph_items, max_idx, history_max) =
-            net.get_netstring(net_graph_max)?;
+        let (netinfo_string, graph, max_idx, history_max) = net.get_netstring(net_graph_max)?;
+        let graph_items = graph.get_items();
         let netinfo_parts: Vec<&str> = netinfo_string.split_whitespace().collect();
 
         if is_empty {
@@ -317,44 +827,18 @@ fn main() -> Result<(), Error> {
             {
                 graph_obj.full_text = history_max;
                 if (net_graph_max.is_some() || net_graph_is_dynamic) && !graph_items.is_empty() {
-                    match graph_items[max_idx].get_value_type() {
-                        proc::GraphItemType::Download => graph_obj.color = Some("#ff8888ff".into()),
-                        proc::GraphItemType::Upload => graph_obj.color = Some("#88ff88ff".into()),
-                        proc::GraphItemType::Both => graph_obj.color = Some("#ffff88ff".into()),
-                    }
+                    graph_obj.color = graph_items[max_idx]
+                        .get_value_type()
+                        .get_color()
+                        .map(|color| color.to_owned());
                 }
             }
 
             if (net_graph_max.is_some() || net_graph_is_dynamic)
                 && let Some(graph_obj) = array.get_by_name_mut("net_graph")
             {
-                let mut text = String::new();
-                for item in graph_items.iter() {
-                    match item.get_value_type() {
-                        proc::GraphItemType::Download => {
-                            write!(
-                                &mut text,
-                                "<span color=\"#ff8888ff\">{}</span>",
-                                item.get_value()
-                            )?;
-                        }
-                        proc::GraphItemType::Upload => {
-                            write!(
-                                &mut text,
-                                "<span color=\"#88ff88ff\">{}</span>",
-                                item.get_value()
-                            )?;
-                        }
-                        proc::GraphItemType::Both => {
-                            write!(
-                                &mut text,
-                                "<span color=\"#ffff88ff\">{}</span>",
-                                item.get_value()
-                            )?;
-                        }
-                    }
-                }
-                graph_obj.full_text = text;
+                graph_obj.full_text =
+                    graph.to_pango_string(net_graph_color_mode, &net_graph_color_ramp)?;
             }
 
             if let Some(down_object) = array.get_by_name_mut("net_down") {
@@ -379,12 +863,101 @@ fn main() -> Result<(), Error> {
     signal_handling::handle_signal(libc::SIGINT, handle_signal);
     signal_handling::handle_signal(libc::SIGHUP, handle_signal);
     signal_handling::handle_signal(libc::SIGTERM, handle_signal);
+    if let Some(signal_str) = args_result.map.get("refresh-signal") {
+        let signal: Option<c_int> = signal_str
+            .parse::<c_int>()
+            .ok()
+            .map(|offset| libc::SIGRTMIN() + offset)
+            .filter(|signal| (libc::SIGRTMIN()..=libc::SIGRTMAX()).contains(signal));
+        if let Some(signal) = signal {
+            signal_handling::handle_signal(signal, handle_refresh_signal);
+        } else {
+            let mut stderr_handle = io::stderr().lock();
+            stderr_handle.write_all(
+                "WARNING: Invalid value passed to --refresh-signal=..., ignoring...\n".as_bytes(),
+            )?;
+        }
+    }
 
+    let click_receiver = if click_events_enabled {
+        Some(click_events::spawn_reader(thread::current()))
+    } else {
+        None
+    };
+
+    // Clicks, signals, and watcher threads unpark the main thread before the
+    // interval has passed. Only the blocks that those can change are updated
+    // then, so that rates and graphs are still based on whole intervals.
+    let mut is_tick: bool = true;
+    let mut last_tick = Instant::now();
+    // Read once per tick, as it walks all power supplies.
+    let needs_ac_online: bool = power_info.is_some()
+        || (batt_info_enabled && args_result.map.contains_key("acpi-builtin-plug"))
+        || battery_interval.is_some();
+    let mut ac_online: Option<bool> = None;
     while IS_RUNNING.load(std::sync::atomic::Ordering::Acquire) {
         let is_empty = array.is_empty();
+        if is_tick {
+            last_tick = Instant::now();
+            if needs_ac_online {
+                ac_online = power::is_ac_online();
+            }
+        }
+
+        // click events
+        if let Some(receiver) = click_receiver.as_ref() {
+            while let Ok(event) = receiver.try_recv() {
+                match event.name.as_deref() {
+                    Some("net_link") => {
+                        if let Some(net_link) = net_link_info.as_mut() {
+                            match event.button {
+                                click_events::BUTTON_LEFT => net_link.next_view(),
+                                click_events::BUTTON_RIGHT => net_link.prev_view(),
+                                _ => (),
+                            }
+                        }
+                    }
+                    Some("cpufreq") => {
+                        if let Some(cpufreq) = cpufreq_info.as_mut() {
+                            match event.button {
+                                click_events::BUTTON_LEFT => cpufreq.cycle_governor(),
+                                click_events::BUTTON_RIGHT => cpufreq.cycle_epp(),
+                                _ => (),
+                            }
+                        }
+                    }
+                    Some("mounts") => {
+                        if let Some(mounts) = mounts_info.as_ref()
+                            && event.button == click_events::BUTTON_LEFT
+                        {
+                            mounts.unmount_last();
+                        }
+                    }
+                    Some("backlight") => {
+                        if let Some(backlight) = backlight_info.as_mut() {
+                            match event.button {
+                                click_events::BUTTON_SCROLL_UP => backlight.adjust(1),
+                                click_events::BUTTON_SCROLL_DOWN => backlight.adjust(-1),
+                                _ => (),
+                            }
+                        }
+                    }
+                    Some("rfkill") => {
+                        if let Some(rfkill) = rfkill_info.as_ref() {
+                            match event.button {
+                                click_events::BUTTON_LEFT => rfkill.toggle_airplane_mode(),
+                                click_events::BUTTON_RIGHT => rfkill.toggle_bluetooth(),
+                                _ => (),
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
 
         // network traffic
-        if let Some(net) = net_obj.as_mut() {
+        if is_tick && let Some(net) = net_obj.as_mut() {
             if let Err(e) = handle_net(is_empty, net, &mut array) {
                 let mut stderr_handle = io::stderr().lock();
                 stderr_handle.write_all(format!("{}\n", e).as_bytes())?;
@@ -397,102 +970,363 @@ fn main() -> Result<(), Error> {
             }
         }
 
+        // net usage
+        if is_tick && let Some(usage) = data_usage.as_mut() {
+            update_block(
+                &mut array,
+                is_empty,
+                "net_usage",
+                "NETUSAGE ERROR",
+                None,
+                |obj| {
+                    if let Some(net) = net_obj.as_ref() {
+                        usage.update(net.get_dev_counters(), obj);
+                    } else {
+                        obj.update_as_error("NETUSAGE ERROR".to_owned());
+                    }
+                    Ok(())
+                },
+            )?;
+        }
+
+        // net link info
+        if let Some(net_link) = net_link_info.as_mut() {
+            update_block(
+                &mut array,
+                is_empty,
+                "net_link",
+                "NETLINK ERROR",
+                None,
+                |obj| {
+                    if let Some(net) = net_obj.as_ref() {
+                        net_link.update(net, obj)
+                    } else {
+                        obj.update_as_error("NETLINK ERROR".to_owned());
+                        Ok(())
+                    }
+                },
+            )?;
+        }
+
+        // wireless
+        if is_tick && let Some(wireless) = wireless_info.as_mut() {
+            let active_dev_name: Option<&str> = net_obj.as_ref().map(|net| net.get_dev_name());
+            update_block(
+                &mut array,
+                is_empty,
+                "wireless",
+                "WIRELESS ERROR",
+                Some(&mut wireless_info_error),
+                |obj| wireless.update(active_dev_name, obj),
+            )?;
+        }
+
+        // vpn
+        if is_tick && let Some(vpn) = vpn_info.as_mut() {
+            update_block(
+                &mut array,
+                is_empty,
+                "vpn",
+                "VPN ERROR",
+                Some(&mut vpn_info_error),
+                |obj| vpn.update(obj),
+            )?;
+        }
+
+        // cpu
+        if is_tick && let Some(cpu) = cpu_info.as_mut() {
+            if is_empty {
+                sparklines.push_object("cpu", &mut array);
+            }
+            update_block(
+                &mut array,
+                is_empty,
+                "cpu",
+                "CPU ERROR",
+                Some(&mut cpu_info_error),
+                |obj| cpu.update(obj),
+            )?;
+            if !cpu_info_error {
+                sparklines.update("cpu", Some(cpu.get_usage()), &mut array)?;
+            }
+        }
+
+        // cpufreq
+        if let Some(cpufreq) = cpufreq_info.as_mut() {
+            update_block(
+                &mut array,
+                is_empty,
+                "cpufreq",
+                "CPUFREQ ERROR",
+                Some(&mut cpufreq_info_error),
+                |obj| cpufreq.update(obj),
+            )?;
+        }
+
+        // temperature
+        if is_tick && let Some(thermal) = thermal_info.as_mut() {
+            if is_empty {
+                sparklines.push_object("temperature", &mut array);
+            }
+            update_block(
+                &mut array,
+                is_empty,
+                "temperature",
+                "TEMP ERROR",
+                Some(&mut thermal_info_error),
+                |obj| thermal.update(obj),
+            )?;
+            if !thermal_info_error {
+                sparklines.update("temperature", thermal.get_temp(), &mut array)?;
+            }
+        }
+
+        // fan
+        if is_tick && let Some(fan) = fan_info.as_mut() {
+            update_block(
+                &mut array,
+                is_empty,
+                "fan",
+                "FAN ERROR",
+                Some(&mut fan_info_error),
+                |obj| fan.update(obj),
+            )?;
+        }
+
         // meminfo
-        {
-            let meminfo_result = proc::get_meminfo();
-            let meminfo_string: String = if let Err(e) = meminfo_result {
-                let mut stderr_handle = io::stderr().lock();
-                stderr_handle.write_all(format!("{}\n", e).as_bytes())?;
-                String::from("MEMINFO ERROR")
-            } else {
-                meminfo_result.unwrap()
-            };
+        if is_tick {
             if is_empty {
-                let meminfo_obj = SwaybarObject::from_string("meminfo".to_owned(), meminfo_string);
-                array.push_object(meminfo_obj);
-            } else if let Some(meminfo_obj) = array.get_by_name_mut("meminfo") {
-                meminfo_obj.update_as_generic(meminfo_string, None);
+                sparklines.push_object("meminfo", &mut array);
             }
+            update_block(
+                &mut array,
+                is_empty,
+                "meminfo",
+                "MEMINFO ERROR",
+                None,
+                |obj| mem_info.update(obj),
+            )?;
+            sparklines.update("meminfo", mem_info.get_percent(), &mut array)?;
+        }
+
+        // psi
+        if is_tick && let Some(psi) = psi_info.as_mut() {
+            update_block(
+                &mut array,
+                is_empty,
+                "psi",
+                "PSI ERROR",
+                Some(&mut psi_info_error),
+                |obj| psi.update(obj),
+            )?;
+        }
+
+        // diskio
+        if is_tick && let Some(diskio) = diskio_info.as_mut() {
+            if is_empty && let Some(graph) = diskio.get_graph() {
+                let mut graph_obj = SwaybarObject::from_string(
+                    "diskio_graph".to_owned(),
+                    " ".repeat(graph.get_items().len()),
+                );
+                graph_obj.markup = Some("pango".to_owned());
+                array.push_object(graph_obj);
+            }
+            update_block(
+                &mut array,
+                is_empty,
+                "diskio",
+                "DISKIO ERROR",
+                Some(&mut diskio_info_error),
+                |obj| diskio.update(obj),
+            )?;
+            if let Some(graph) = diskio.get_graph()
+                && !diskio_info_error
+                && let Some(graph_obj) = array.get_by_name_mut("diskio_graph")
+            {
+                graph_obj.full_text =
+                    graph.to_pango_string(diskio_graph_color_mode, &diskio_graph_color_ramp)?;
+            }
+        }
+
+        // fs
+        if is_tick && let Some(fs_usage) = fs_usage_info.as_mut() {
+            update_block(
+                &mut array,
+                is_empty,
+                "fs",
+                "FS ERROR",
+                Some(&mut fs_usage_info_error),
+                |obj| fs_usage.update(obj),
+            )?;
+        }
+
+        // mounts
+        if let Some(mounts) = mounts_info.as_mut() {
+            update_block(
+                &mut array,
+                is_empty,
+                "mounts",
+                "MOUNTS ERROR",
+                Some(&mut mounts_info_error),
+                |obj| mounts.update(obj),
+            )?;
+        }
+
+        // processes
+        if is_tick && let Some(processes) = processes_info.as_mut() {
+            update_block(
+                &mut array,
+                is_empty,
+                "processes",
+                "PROCESSES ERROR",
+                Some(&mut processes_info_error),
+                |obj| processes.update(obj),
+            )?;
+        }
+
+        // top
+        if is_tick && let Some(top) = top_info.as_mut() {
+            update_block(
+                &mut array,
+                is_empty,
+                "top",
+                "TOP ERROR",
+                Some(&mut top_info_error),
+                |obj| top.update(obj),
+            )?;
+        }
+
+        // privacy
+        if is_tick && let Some(privacy) = privacy_info.as_mut() {
+            update_block(
+                &mut array,
+                is_empty,
+                "privacy",
+                "PRIVACY ERROR",
+                Some(&mut privacy_info_error),
+                |obj| privacy.update(obj),
+            )?;
+        }
+
+        // audio_playback
+        if is_tick && let Some(audio_playback) = audio_playback_info.as_mut() {
+            if is_empty {
+                let mut new_object = SwaybarObject::new("audio_playback".to_owned());
+                audio_playback.update(&mut new_object);
+                array.push_object(new_object);
+            } else if let Some(obj) = array.get_by_name_mut("audio_playback") {
+                audio_playback.update(obj);
+            }
+        }
+
+        // backlight
+        if let Some(backlight) = backlight_info.as_mut() {
+            update_block(
+                &mut array,
+                is_empty,
+                "backlight",
+                "BACKLIGHT ERROR",
+                Some(&mut backlight_info_error),
+                |obj| backlight.update(obj),
+            )?;
+        }
+
+        // lock_keys
+        if let Some(lock_keys) = lock_keys_info.as_mut() {
+            update_block(
+                &mut array,
+                is_empty,
+                "lock_keys",
+                "LOCK KEYS ERROR",
+                Some(&mut lock_keys_info_error),
+                |obj| lock_keys.update(obj),
+            )?;
+        }
+
+        // rfkill
+        if let Some(rfkill) = rfkill_info.as_mut() {
+            update_block(
+                &mut array,
+                is_empty,
+                "rfkill",
+                "RFKILL ERROR",
+                Some(&mut rfkill_info_error),
+                |obj| rfkill.update(obj),
+            )?;
         }
 
         // regex_cmds
-        {
+        if is_tick {
             for (idx, (cmd, args, regex)) in cmds.iter().enumerate() {
                 let cmd_result = external::get_cmd_output(cmd, args, regex);
+                let block_name = format!("regex_cmd_{}", idx);
                 if let Ok(cmd_struct) = cmd_result {
+                    let value: Option<f64> = graph::get_first_number(&cmd_struct.matched);
                     if is_empty {
-                        let mut cmd_obj = SwaybarObject::from_string(
-                            format!("regex_cmd_{}", idx),
-                            cmd_struct.matched,
-                        );
+                        sparklines.push_object(&block_name, &mut array);
+                        let mut cmd_obj =
+                            SwaybarObject::from_string(block_name.clone(), cmd_struct.matched);
                         cmd_obj.color = cmd_struct.color;
                         array.push_object(cmd_obj);
-                    } else if let Some(cmd_obj) =
-                        array.get_by_name_mut(&format!("regex_cmd_{}", idx))
-                    {
+                    } else if let Some(cmd_obj) = array.get_by_name_mut(&block_name) {
                         cmd_obj.update_as_generic(cmd_struct.matched, cmd_struct.color);
                     }
+                    sparklines.update(&block_name, value, &mut array)?;
                 } else if let Err(e) = cmd_result {
                     let mut stderr_handle = io::stderr().lock();
                     stderr_handle.write_all(format!("{}\n", e).as_bytes())?;
                     if is_empty {
+                        sparklines.push_object(&block_name, &mut array);
                         let cmd_obj = SwaybarObject::from_error_string(
-                            format!("regex_cmd_{}", idx),
+                            block_name.clone(),
                             "REGEX_CMD ERROR".into(),
                         );
                         array.push_object(cmd_obj);
-                    } else if let Some(cmd_obj) =
-                        array.get_by_name_mut(&format!("regex_cmd_{}", idx))
-                    {
+                    } else if let Some(cmd_obj) = array.get_by_name_mut(&block_name) {
                         cmd_obj.update_as_error("REGEX_CMD ERROR".into());
                     }
                 }
             }
         }
 
+        // power
+        if is_tick && let Some(power) = power_info.as_mut() {
+            update_block(
+                &mut array,
+                is_empty,
+                "power",
+                "POWER ERROR",
+                Some(&mut power_info_error),
+                |obj| power.update(ac_online, obj),
+            )?;
+        }
+
         // batt_info
-        if batt_info_enabled {
-            if is_empty {
-                let mut new_object = SwaybarObject::new("battinfo".to_owned());
-                let result = batt_info.update(&mut new_object);
-                if let Err(e) = result {
-                    new_object.update_as_error("BATTINFO ERROR".to_owned());
-                    array.push_object(new_object);
-                    batt_info_error = true;
-                    let mut stderr_handle = io::stderr().lock();
-                    stderr_handle.write_all(format!("{}\n", e).as_bytes())?;
-                } else {
-                    array.push_object(new_object);
-                }
-            } else if let Some(obj) = array.get_by_name_mut("battinfo")
-                && !batt_info_error
-            {
-                let result = batt_info.update(obj);
-                if let Err(e) = result {
-                    obj.update_as_error("BATTINFO ERROR".to_owned());
-                    batt_info_error = true;
-                    let mut stderr_handle = io::stderr().lock();
-                    stderr_handle.write_all(format!("{}\n", e).as_bytes())?;
-                }
-            }
+        if is_tick && batt_info_enabled {
+            update_block(
+                &mut array,
+                is_empty,
+                "battinfo",
+                "BATTINFO ERROR",
+                Some(&mut batt_info_error),
+                |obj| batt_info.update(ac_online, obj),
+            )?;
         }
 
         // loadavg
-        {
-            let loadavg_result = proc::get_loadavg();
-            let loadavg_string: String = if let Err(e) = loadavg_result {
-                let mut stderr_handle = io::stderr().lock();
-                stderr_handle.write_all(format!("{}\n", e).as_bytes())?;
-                String::from("LOADAVG ERROR")
-            } else {
-                loadavg_result.unwrap()
-            };
+        if is_tick {
             if is_empty {
-                let loadavg_obj = SwaybarObject::from_string("loadavg".to_owned(), loadavg_string);
-                array.push_object(loadavg_obj);
-            } else if let Some(loadavg_obj) = array.get_by_name_mut("loadavg") {
-                loadavg_obj.update_as_generic(loadavg_string, None);
+                sparklines.push_object("loadavg", &mut array);
             }
+            update_block(
+                &mut array,
+                is_empty,
+                "loadavg",
+                "LOADAVG ERROR",
+                None,
+                |obj| loadavg_info.update(obj),
+            )?;
+            sparklines.update("loadavg", loadavg_info.get_load(), &mut array)?;
         }
 
         // time
@@ -505,7 +1339,15 @@ fn main() -> Result<(), Error> {
         }
 
         println!("{}", array);
-        thread::park_timeout(interval);
+        // Use the battery interval when an AC adapter is known to be unplugged.
+        is_tick = wait_for_tick(last_tick, interval, battery_interval, &mut ac_online);
+    }
+
+    if let Some(usage) = data_usage.as_mut()
+        && let Err(e) = usage.save()
+    {
+        let mut stderr_handle = io::stderr().lock();
+        stderr_handle.write_all(format!("{}\n", e).as_bytes())?;
     }
 
     Ok(())
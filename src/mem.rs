@@ -0,0 +1,179 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::error::Error;
+use crate::swaybar_object::SwaybarObject;
+use crate::sysfs::{get_sorted_entries, read_trimmed};
+use crate::threshold::{ThresholdLevel, Thresholds};
+use crate::units::format_bytes;
+
+const DEFAULT_MEM_FORMAT: &str = "{used} / {total}";
+
+/// Values (in bytes) from /proc/meminfo.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct MemStats {
+    total: u64,
+    free: u64,
+    available: u64,
+    buffers: u64,
+    cached: u64,
+    sreclaimable: u64,
+    swap_total: u64,
+    swap_free: u64,
+    zswap: u64,
+}
+
+impl MemStats {
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.available)
+    }
+
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.used() as f64 / self.total as f64 * 100.0
+        }
+    }
+
+    pub fn swap_used(&self) -> u64 {
+        self.swap_total.saturating_sub(self.swap_free)
+    }
+
+    pub fn swap_percent(&self) -> f64 {
+        if self.swap_total == 0 {
+            0.0
+        } else {
+            self.swap_used() as f64 / self.swap_total as f64 * 100.0
+        }
+    }
+}
+
+pub fn read_meminfo() -> Result<MemStats, Error> {
+    let mut meminfo_string = String::new();
+    {
+        let mut meminfo: File = File::open("/proc/meminfo")?;
+        meminfo.read_to_string(&mut meminfo_string)?;
+    }
+
+    let mut stats: MemStats = Default::default();
+    for line in meminfo_string.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let field: &mut u64 = match key {
+            "MemTotal" => &mut stats.total,
+            "MemFree" => &mut stats.free,
+            "MemAvailable" => &mut stats.available,
+            "Buffers" => &mut stats.buffers,
+            "Cached" => &mut stats.cached,
+            "SReclaimable" => &mut stats.sreclaimable,
+            "SwapTotal" => &mut stats.swap_total,
+            "SwapFree" => &mut stats.swap_free,
+            "Zswap" => &mut stats.zswap,
+            _ => continue,
+        };
+        // Values are in kB (actually KiB).
+        let kib: u64 = value
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .parse()?;
+        *field = kib * 1024;
+    }
+
+    if stats.total == 0 {
+        return Err("meminfo: failed to parse MemTotal".to_owned().into());
+    }
+
+    Ok(stats)
+}
+
+/// Returns the total compressed size (in bytes) of all zram devices.
+fn get_zram_compressed() -> u64 {
+    get_sorted_entries(Path::new("/sys/block"), "zram")
+        .iter()
+        .filter_map(|dir| read_trimmed(&dir.join("mm_stat")))
+        .filter_map(|mm_stat| {
+            // The second field is "compr_data_size".
+            mm_stat.split_whitespace().nth(1)?.parse::<u64>().ok()
+        })
+        .sum()
+}
+
+pub struct MemInfo {
+    format: String,
+    stats: Option<MemStats>,
+    thresholds: Option<Thresholds>,
+    swap_thresholds: Option<Thresholds>,
+}
+
+impl MemInfo {
+    /// `format` may contain "{used}", "{available}", "{free}", "{total}",
+    /// "{percent}", "{buffers}", "{cached}", "{swap_used}", "{swap_total}",
+    /// "{swap_percent}", "{zram}", and "{zswap}".
+    ///
+    /// `thresholds` and `swap_thresholds` are percentages of memory and swap
+    /// used.
+    pub fn new(
+        format: Option<&str>,
+        thresholds: Option<Thresholds>,
+        swap_thresholds: Option<Thresholds>,
+    ) -> Self {
+        Self {
+            format: format.unwrap_or(DEFAULT_MEM_FORMAT).to_owned(),
+            stats: None,
+            thresholds,
+            swap_thresholds,
+        }
+    }
+
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        self.stats = None;
+        let stats = read_meminfo()?;
+
+        let mut text: String = self
+            .format
+            .replace("{used}", &format_bytes(stats.used() as f64))
+            .replace("{available}", &format_bytes(stats.available as f64))
+            .replace("{free}", &format_bytes(stats.free as f64))
+            .replace("{total}", &format_bytes(stats.total as f64))
+            .replace("{percent}", &format!("{:.0}", stats.percent()))
+            .replace("{buffers}", &format_bytes(stats.buffers as f64))
+            .replace(
+                "{cached}",
+                &format_bytes((stats.cached + stats.sreclaimable) as f64),
+            )
+            .replace("{swap_used}", &format_bytes(stats.swap_used() as f64))
+            .replace("{swap_total}", &format_bytes(stats.swap_total as f64))
+            .replace("{swap_percent}", &format!("{:.0}", stats.swap_percent()))
+            .replace("{zswap}", &format_bytes(stats.zswap as f64));
+        // Only read zram stats if they are used.
+        if text.contains("{zram}") {
+            text = text.replace("{zram}", &format_bytes(get_zram_compressed() as f64));
+        }
+
+        let mut level = self
+            .thresholds
+            .map(|thresholds| thresholds.get_level(stats.percent()))
+            .unwrap_or(ThresholdLevel::Normal);
+        if let Some(swap_thresholds) = self.swap_thresholds
+            && stats.swap_total > 0
+        {
+            let swap_level = swap_thresholds.get_level(stats.swap_percent());
+            if swap_level > level {
+                level = swap_level;
+            }
+        }
+        object.update_as_threshold(text, level);
+        self.stats = Some(stats);
+
+        Ok(())
+    }
+
+    /// Returns the percentage of memory used from the last successful update.
+    pub fn get_percent(&self) -> Option<f64> {
+        self.stats.map(|stats| stats.percent())
+    }
+}
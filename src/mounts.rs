@@ -0,0 +1,304 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::os::fd::AsRawFd;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, Thread};
+
+use crate::args;
+use crate::error::Error;
+use crate::swaybar_object::SwaybarObject;
+
+const MOUNTINFO_PATH: &str = "/proc/self/mountinfo";
+const REMOVABLE_PREFIXES: [&str; 2] = ["/run/media/", "/media/"];
+const NETWORK_FS_TYPES: [&str; 9] = [
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "9p",
+    "davfs",
+    "fuse.sshfs",
+    "fuse.rclone",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+struct Mount {
+    mount_point: String,
+    fs_type: String,
+    source: String,
+}
+
+// Mount points in mountinfo escape space, tab, newline, and backslash as
+// octal, such as "\040".
+fn unescape_octal(string: &str) -> String {
+    let bytes = string.as_bytes();
+    let mut unescaped: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'\\'
+            && let Some(octal) = string.get(idx + 1..idx + 4)
+            && octal.bytes().all(|b| (b'0'..=b'7').contains(&b))
+            && let Ok(byte) = u8::from_str_radix(octal, 8)
+        {
+            unescaped.push(byte);
+            idx += 4;
+        } else {
+            unescaped.push(bytes[idx]);
+            idx += 1;
+        }
+    }
+
+    String::from_utf8_lossy(&unescaped).into_owned()
+}
+
+fn parse_mountinfo(mountinfo: &str) -> Vec<Mount> {
+    let mut mounts: Vec<Mount> = Vec::new();
+    for line in mountinfo.lines() {
+        // The optional fields end with a "-" separator, followed by the
+        // filesystem type and the mount source.
+        let Some((front, back)) = line.split_once(" - ") else {
+            continue;
+        };
+        let (Some(mount_point), mut back_parts) =
+            (front.split_whitespace().nth(4), back.split_whitespace())
+        else {
+            continue;
+        };
+        let (Some(fs_type), Some(source)) = (back_parts.next(), back_parts.next()) else {
+            continue;
+        };
+        mounts.push(Mount {
+            mount_point: unescape_octal(mount_point),
+            fs_type: fs_type.to_owned(),
+            source: unescape_octal(source),
+        });
+    }
+
+    mounts
+}
+
+// Checks the "removable" attribute of the block device, or of its parent if
+// it is a partition.
+fn is_removable_device(source: &str) -> bool {
+    let Some(dev_name) = source.strip_prefix("/dev/") else {
+        return false;
+    };
+    let Ok(dev_path) = fs::canonicalize(Path::new("/sys/class/block").join(dev_name)) else {
+        return false;
+    };
+
+    [Some(dev_path.as_path()), dev_path.parent()]
+        .into_iter()
+        .flatten()
+        .any(|path| {
+            fs::read_to_string(path.join("removable"))
+                .is_ok_and(|removable| removable.trim() == "1")
+        })
+}
+
+impl Mount {
+    fn is_watched(&self) -> bool {
+        NETWORK_FS_TYPES.contains(&self.fs_type.as_str())
+            || REMOVABLE_PREFIXES
+                .iter()
+                .any(|prefix| self.mount_point.starts_with(prefix))
+            || is_removable_device(&self.source)
+    }
+
+    fn get_name(&self) -> &str {
+        self.mount_point
+            .rsplit('/')
+            .find(|s| !s.is_empty())
+            .unwrap_or(&self.mount_point)
+    }
+}
+
+/// Spawns a thread that polls mountinfo, which signals POLLPRI when the mount
+/// table changes. `changed` is set and `main_thread` is unparked on changes.
+fn spawn_watcher(changed: Arc<AtomicBool>, main_thread: Thread) -> Result<(), Error> {
+    let mountinfo_file = File::open(MOUNTINFO_PATH)?;
+
+    thread::spawn(move || {
+        loop {
+            let mut pollfd = libc::pollfd {
+                fd: mountinfo_file.as_raw_fd(),
+                events: libc::POLLPRI,
+                revents: 0,
+            };
+            let result = unsafe { libc::poll(&mut pollfd, 1, -1) };
+            if result < 0 {
+                if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                let mut stderr_handle = io::stderr().lock();
+                stderr_handle
+                    .write_all(
+                        format!(
+                            "WARNING: mounts: Failed to poll {}: {}\n",
+                            MOUNTINFO_PATH,
+                            io::Error::last_os_error()
+                        )
+                        .as_bytes(),
+                    )
+                    .ok();
+                break;
+            }
+            if pollfd.revents & (libc::POLLPRI | libc::POLLERR) != 0 {
+                // poll() only reports each change once, so the file doesn't
+                // need to be read here.
+                changed.store(true, Ordering::Release);
+                main_thread.unpark();
+            }
+        }
+    });
+
+    Ok(())
+}
+
+pub struct MountsInfo {
+    mounts: Vec<Mount>,
+    changed: Arc<AtomicBool>,
+    watcher_spawned: bool,
+    unmount_cmd: Option<(String, Vec<String>)>,
+}
+
+impl MountsInfo {
+    /// `unmount_cmd` is in the same format as `--regex-cmd` without the regex,
+    /// and is run with the mount point appended when the block is clicked.
+    pub fn new(unmount_cmd: Option<&str>) -> Self {
+        let unmount_cmd = unmount_cmd.map(args::parse_cmd);
+
+        Self {
+            mounts: Vec::new(),
+            changed: Arc::new(AtomicBool::new(true)),
+            watcher_spawned: false,
+            unmount_cmd,
+        }
+    }
+
+    pub fn has_click_actions(&self) -> bool {
+        self.unmount_cmd.is_some()
+    }
+
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        if !self.watcher_spawned {
+            spawn_watcher(self.changed.clone(), thread::current())?;
+            self.watcher_spawned = true;
+        }
+
+        if self.changed.swap(false, Ordering::AcqRel) {
+            let mountinfo_string = fs::read_to_string(MOUNTINFO_PATH)?;
+            self.mounts = parse_mountinfo(&mountinfo_string)
+                .into_iter()
+                .filter(|mount| mount.is_watched())
+                .collect();
+        }
+
+        if self.mounts.is_empty() {
+            object.update_as_hidden();
+        } else {
+            let names: Vec<&str> = self.mounts.iter().map(|mount| mount.get_name()).collect();
+            object.update_as_generic(format!("MNT {}", names.join(" ")), None);
+        }
+
+        Ok(())
+    }
+
+    /// Runs the unmount command on the most recently mounted filesystem.
+    pub fn unmount_last(&self) {
+        let (Some((cmd, args)), Some(mount)) = (self.unmount_cmd.as_ref(), self.mounts.last())
+        else {
+            return;
+        };
+
+        let mut cmd_builder = Command::new(cmd);
+        cmd_builder.args(args).arg(&mount.mount_point);
+        let mount_point = mount.mount_point.clone();
+        // Wait in another thread so that the main loop isn't blocked.
+        thread::spawn(move || {
+            let result = cmd_builder.status();
+            let error: Option<String> = match result {
+                Ok(status) if status.success() => None,
+                Ok(status) => Some(status.to_string()),
+                Err(e) => Some(e.to_string()),
+            };
+            if let Some(error) = error {
+                let mut stderr_handle = io::stderr().lock();
+                stderr_handle
+                    .write_all(
+                        format!(
+                            "WARNING: mounts: Failed to unmount \"{}\": {}\n",
+                            mount_point, error
+                        )
+                        .as_bytes(),
+                    )
+                    .ok();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unescape_octal_escapes() {
+        assert_eq!(unescape_octal(""), "");
+        assert_eq!(unescape_octal("/mnt/usb"), "/mnt/usb");
+        assert_eq!(
+            unescape_octal("/run/media/me/My\\040Disk"),
+            "/run/media/me/My Disk"
+        );
+        assert_eq!(unescape_octal("a\\011b\\012c\\134d"), "a\tb\nc\\d");
+        assert_eq!(unescape_octal("\\303\\251t\\303\\251"), "été");
+    }
+
+    #[test]
+    fn unescape_octal_invalid() {
+        // Too short, not octal, or above 0o377.
+        assert_eq!(unescape_octal("end\\04"), "end\\04");
+        assert_eq!(unescape_octal("a\\089b"), "a\\089b");
+        assert_eq!(unescape_octal("a\\400b"), "a\\400b");
+        assert_eq!(unescape_octal("\\"), "\\");
+    }
+
+    #[test]
+    fn parse_mountinfo_lines() {
+        let mountinfo = "\
+22 28 0:21 / /sys rw,nosuid,nodev,noexec,relatime shared:7 - sysfs sysfs rw
+28 1 259:2 / / rw,relatime shared:1 - ext4 /dev/nvme0n1p2 rw
+35 28 8:17 / /run/media/me/My\\040Disk rw,nosuid,nodev,relatime shared:80 master:1 - vfat /dev/sdb1 rw,fmask=0022
+40 28 0:50 / /mnt/nas rw,relatime - nfs4 nas:/export rw,vers=4.2
+";
+        let mounts = parse_mountinfo(mountinfo);
+        assert_eq!(mounts.len(), 4);
+        assert_eq!(
+            mounts[2],
+            Mount {
+                mount_point: "/run/media/me/My Disk".to_owned(),
+                fs_type: "vfat".to_owned(),
+                source: "/dev/sdb1".to_owned(),
+            }
+        );
+        assert_eq!(mounts[3].mount_point, "/mnt/nas");
+        assert_eq!(mounts[3].fs_type, "nfs4");
+        assert_eq!(mounts[3].source, "nas:/export");
+    }
+
+    #[test]
+    fn parse_mountinfo_invalid_lines() {
+        assert!(parse_mountinfo("").is_empty());
+        // No separator, too few fields before it, or no source after it.
+        let mountinfo = "\
+22 28 0:21 / /sys rw sysfs sysfs rw
+22 28 0:21 / - sysfs sysfs rw
+22 28 0:21 / /sys rw - sysfs
+";
+        assert!(parse_mountinfo(mountinfo).is_empty());
+    }
+}
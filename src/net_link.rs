@@ -0,0 +1,195 @@
+use std::ffi::CStr;
+use std::fs;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+use std::ptr::null_mut;
+
+use crate::error::Error;
+use crate::proc::NetInfo;
+use crate::swaybar_object::SwaybarObject;
+use crate::sysfs::read_trimmed;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum NetLinkView {
+    Addresses,
+    Link,
+    State,
+}
+
+impl NetLinkView {
+    fn next(self) -> Self {
+        match self {
+            NetLinkView::Addresses => NetLinkView::Link,
+            NetLinkView::Link => NetLinkView::State,
+            NetLinkView::State => NetLinkView::Addresses,
+        }
+    }
+
+    fn prev(self) -> Self {
+        match self {
+            NetLinkView::Addresses => NetLinkView::State,
+            NetLinkView::Link => NetLinkView::Addresses,
+            NetLinkView::State => NetLinkView::Link,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct NetLinkInfo {
+    view: NetLinkView,
+}
+
+impl Default for NetLinkInfo {
+    fn default() -> Self {
+        Self {
+            view: NetLinkView::Addresses,
+        }
+    }
+}
+
+impl NetLinkInfo {
+    pub fn next_view(&mut self) {
+        self.view = self.view.next();
+    }
+
+    pub fn prev_view(&mut self) {
+        self.view = self.view.prev();
+    }
+
+    pub fn update(&mut self, net: &NetInfo, object: &mut SwaybarObject) -> Result<(), Error> {
+        let dev_name: String = if net.get_dev_name() == "all" {
+            get_first_up_dev(net)?
+        } else {
+            net.get_dev_name().to_owned()
+        };
+
+        let (operstate, carrier) = get_state(&dev_name);
+        let color: Option<String> = if operstate == "up" {
+            None
+        } else {
+            Some("#ff8888ff".into())
+        };
+
+        let text: String = match self.view {
+            NetLinkView::Addresses => {
+                let addresses = get_addresses(&dev_name)?;
+                if addresses.is_empty() {
+                    format!("{}: no address", dev_name)
+                } else {
+                    format!("{}: {}", dev_name, addresses.join(" "))
+                }
+            }
+            NetLinkView::Link => {
+                let (speed, duplex) = get_link(&dev_name);
+                format!("{}: {} {}", dev_name, speed, duplex)
+            }
+            NetLinkView::State => format!(
+                "{}: {} {}",
+                dev_name,
+                operstate,
+                if carrier { "carrier" } else { "no-carrier" }
+            ),
+        };
+
+        object.update_as_generic(text, color);
+
+        Ok(())
+    }
+}
+
+fn read_sys_net(dev_name: &str, file: &str) -> Option<String> {
+    read_trimmed(&Path::new("/sys/class/net").join(dev_name).join(file))
+}
+
+// Used when net-dev is "all", as there is no single device to show info for.
+fn get_first_up_dev(net: &NetInfo) -> Result<String, Error> {
+    let mut dev_names: Vec<String> = fs::read_dir("/sys/class/net")?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| net.check_netdev_wb(name))
+        .collect();
+    dev_names.sort();
+
+    for dev_name in dev_names {
+        if read_sys_net(&dev_name, "operstate").as_deref() == Some("up") {
+            return Ok(dev_name);
+        }
+    }
+
+    Err("NetLinkInfo: no net device is up".to_owned().into())
+}
+
+// Returns (operstate, carrier).
+fn get_state(dev_name: &str) -> (String, bool) {
+    let operstate = read_sys_net(dev_name, "operstate").unwrap_or_else(|| "unknown".into());
+    // Reading "carrier" fails with EINVAL when the device is down.
+    let carrier = read_sys_net(dev_name, "carrier").as_deref() == Some("1");
+
+    (operstate, carrier)
+}
+
+// Returns (speed, duplex).
+fn get_link(dev_name: &str) -> (String, String) {
+    // Wireless devices usually don't report a speed, and a device that is
+    // down reports "-1".
+    let speed: String = match read_sys_net(dev_name, "speed").map(|s| s.parse::<i64>()) {
+        Some(Ok(speed)) if speed > 0 => {
+            if speed >= 1000 && speed % 1000 == 0 {
+                format!("{} Gb/s", speed / 1000)
+            } else {
+                format!("{} Mb/s", speed)
+            }
+        }
+        _ => "? Mb/s".to_owned(),
+    };
+    let duplex: String = read_sys_net(dev_name, "duplex").unwrap_or_else(|| "unknown".into());
+
+    (speed, duplex)
+}
+
+// Returns IPv4 addresses first, then non-link-local IPv6 addresses.
+fn get_addresses(dev_name: &str) -> Result<Vec<String>, Error> {
+    let mut ipv4: Vec<String> = Vec::new();
+    let mut ipv6: Vec<String> = Vec::new();
+
+    let mut ifaddrs: *mut libc::ifaddrs = null_mut();
+    if unsafe { libc::getifaddrs(&mut ifaddrs as *mut *mut libc::ifaddrs) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    let mut current = ifaddrs;
+    while !current.is_null() {
+        let ifaddr: &libc::ifaddrs = unsafe { &*current };
+        current = ifaddr.ifa_next;
+
+        if ifaddr.ifa_addr.is_null() || ifaddr.ifa_name.is_null() {
+            continue;
+        }
+        let name = unsafe { CStr::from_ptr(ifaddr.ifa_name) };
+        if name.to_bytes() != dev_name.as_bytes() {
+            continue;
+        }
+
+        match unsafe { (*ifaddr.ifa_addr).sa_family } as libc::c_int {
+            libc::AF_INET => {
+                let addr = unsafe { &*(ifaddr.ifa_addr as *const libc::sockaddr_in) };
+                ipv4.push(Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr)).to_string());
+            }
+            libc::AF_INET6 => {
+                let addr = unsafe { &*(ifaddr.ifa_addr as *const libc::sockaddr_in6) };
+                let addr = Ipv6Addr::from(addr.sin6_addr.s6_addr);
+                if !addr.is_unicast_link_local() {
+                    ipv6.push(addr.to_string());
+                }
+            }
+            _ => (),
+        }
+    }
+
+    unsafe {
+        libc::freeifaddrs(ifaddrs);
+    }
+
+    ipv4.append(&mut ipv6);
+    Ok(ipv4)
+}
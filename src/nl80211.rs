@@ -0,0 +1,360 @@
+use std::ffi::CString;
+use std::mem::{MaybeUninit, size_of};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+use crate::error::Error;
+
+const NLMSG_HDR_LEN: usize = 16;
+const GENL_HDR_LEN: usize = 4;
+const NLA_HDR_LEN: usize = 4;
+
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+const NLM_F_REQUEST: u16 = 1;
+
+const GENL_ID_CTRL: u16 = 0x10;
+const CTRL_CMD_GETFAMILY: u8 = 3;
+const CTRL_ATTR_FAMILY_ID: u16 = 1;
+const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+
+const NL80211_CMD_GET_INTERFACE: u8 = 5;
+const NL80211_ATTR_IFINDEX: u16 = 3;
+const NL80211_ATTR_SSID: u16 = 52;
+
+const RECV_BUFFER_SIZE: usize = 8192;
+
+fn align_4(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+/// Builds a generic netlink request with the given attributes.
+pub fn build_request(family: u16, cmd: u8, seq: u32, attrs: &[(u16, &[u8])]) -> Vec<u8> {
+    let mut buf: Vec<u8> = vec![0; NLMSG_HDR_LEN];
+
+    buf.push(cmd);
+    // genl version
+    buf.push(1);
+    buf.extend_from_slice(&[0, 0]);
+
+    for (attr_type, payload) in attrs {
+        let attr_len = NLA_HDR_LEN + payload.len();
+        buf.extend_from_slice(&(attr_len as u16).to_ne_bytes());
+        buf.extend_from_slice(&attr_type.to_ne_bytes());
+        buf.extend_from_slice(payload);
+        buf.resize(align_4(buf.len()), 0);
+    }
+
+    let total_len = buf.len() as u32;
+    buf[0..4].copy_from_slice(&total_len.to_ne_bytes());
+    buf[4..6].copy_from_slice(&family.to_ne_bytes());
+    buf[6..8].copy_from_slice(&NLM_F_REQUEST.to_ne_bytes());
+    buf[8..12].copy_from_slice(&seq.to_ne_bytes());
+    // nlmsg_pid is left as 0 so that the kernel fills it in.
+
+    buf
+}
+
+/// Returns the attributes (type, payload) of the first generic netlink
+/// message in a reply.
+pub fn parse_reply_attrs(reply: &[u8]) -> Result<Vec<(u16, &[u8])>, Error> {
+    if reply.len() < NLMSG_HDR_LEN {
+        return Err("nl80211: reply is too short".to_owned().into());
+    }
+
+    let msg_len = u32::from_ne_bytes(reply[0..4].try_into().unwrap()) as usize;
+    let msg_type = u16::from_ne_bytes(reply[4..6].try_into().unwrap());
+    if msg_len < NLMSG_HDR_LEN || msg_len > reply.len() {
+        return Err("nl80211: reply has invalid length".to_owned().into());
+    }
+
+    match msg_type {
+        NLMSG_ERROR => {
+            if msg_len < NLMSG_HDR_LEN + 4 {
+                return Err("nl80211: error reply is too short".to_owned().into());
+            }
+            let errno =
+                i32::from_ne_bytes(reply[NLMSG_HDR_LEN..NLMSG_HDR_LEN + 4].try_into().unwrap());
+            return Err(std::io::Error::from_raw_os_error(-errno).into());
+        }
+        NLMSG_DONE => return Err("nl80211: got empty reply".to_owned().into()),
+        _ => (),
+    }
+
+    let mut attrs: Vec<(u16, &[u8])> = Vec::new();
+    let mut offset = NLMSG_HDR_LEN + GENL_HDR_LEN;
+    while offset + NLA_HDR_LEN <= msg_len {
+        let attr_len = u16::from_ne_bytes(reply[offset..offset + 2].try_into().unwrap()) as usize;
+        // Mask out NLA_F_NESTED and NLA_F_NET_BYTEORDER.
+        let attr_type =
+            u16::from_ne_bytes(reply[offset + 2..offset + 4].try_into().unwrap()) & 0x3fff;
+        if attr_len < NLA_HDR_LEN || offset + attr_len > msg_len {
+            return Err("nl80211: reply has invalid attribute".to_owned().into());
+        }
+        attrs.push((attr_type, &reply[offset + NLA_HDR_LEN..offset + attr_len]));
+        offset += align_4(attr_len);
+    }
+
+    Ok(attrs)
+}
+
+/// Gets the family id from a reply to CTRL_CMD_GETFAMILY.
+pub fn parse_family_id(reply: &[u8]) -> Result<u16, Error> {
+    parse_reply_attrs(reply)?
+        .into_iter()
+        .find(|(attr_type, payload)| *attr_type == CTRL_ATTR_FAMILY_ID && payload.len() >= 2)
+        .map(|(_, payload)| u16::from_ne_bytes([payload[0], payload[1]]))
+        .ok_or_else(|| Error::from("nl80211: no family id in reply".to_owned()))
+}
+
+/// Gets the SSID from a reply to NL80211_CMD_GET_INTERFACE. Returns `None` if
+/// the interface is not connected.
+pub fn parse_ssid(reply: &[u8]) -> Result<Option<String>, Error> {
+    Ok(parse_reply_attrs(reply)?
+        .into_iter()
+        .find(|(attr_type, _)| *attr_type == NL80211_ATTR_SSID)
+        .map(|(_, payload)| String::from_utf8_lossy(payload).into_owned()))
+}
+
+pub struct Nl80211 {
+    fd: OwnedFd,
+    family_id: u16,
+    seq: u32,
+}
+
+impl Nl80211 {
+    pub fn new() -> Result<Self, Error> {
+        let raw_fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                libc::NETLINK_GENERIC,
+            )
+        };
+        if raw_fd < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let fd = unsafe { OwnedFd::from_raw_fd(raw_fd) };
+
+        let mut addr: MaybeUninit<libc::sockaddr_nl> = MaybeUninit::zeroed();
+        let timeout = libc::timeval {
+            tv_sec: 1,
+            tv_usec: 0,
+        };
+        unsafe {
+            (*addr.as_mut_ptr()).nl_family = libc::AF_NETLINK as libc::sa_family_t;
+            if libc::bind(
+                fd.as_raw_fd(),
+                addr.as_ptr() as *const libc::sockaddr,
+                size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            ) != 0
+            {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            // Don't block the main loop if the kernel never replies.
+            if libc::setsockopt(
+                fd.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                &timeout as *const libc::timeval as *const libc::c_void,
+                size_of::<libc::timeval>() as libc::socklen_t,
+            ) != 0
+            {
+                return Err(std::io::Error::last_os_error().into());
+            }
+        }
+
+        let mut s = Self {
+            fd,
+            family_id: 0,
+            seq: 0,
+        };
+
+        let reply = s.request(
+            GENL_ID_CTRL,
+            CTRL_CMD_GETFAMILY,
+            &[(CTRL_ATTR_FAMILY_NAME, b"nl80211\0")],
+        )?;
+        s.family_id = parse_family_id(&reply)?;
+
+        Ok(s)
+    }
+
+    pub fn get_ssid(&mut self, dev_name: &str) -> Result<Option<String>, Error> {
+        let dev_name_c = CString::new(dev_name)
+            .map_err(|_| Error::from("nl80211: invalid device name".to_owned()))?;
+        let ifindex: u32 = unsafe { libc::if_nametoindex(dev_name_c.as_ptr()) };
+        if ifindex == 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        let reply = self.request(
+            self.family_id,
+            NL80211_CMD_GET_INTERFACE,
+            &[(NL80211_ATTR_IFINDEX, &ifindex.to_ne_bytes())],
+        )?;
+        parse_ssid(&reply)
+    }
+
+    fn request(&mut self, family: u16, cmd: u8, attrs: &[(u16, &[u8])]) -> Result<Vec<u8>, Error> {
+        self.seq = self.seq.wrapping_add(1);
+        let request = build_request(family, cmd, self.seq, attrs);
+
+        loop {
+            let sent = unsafe {
+                libc::send(
+                    self.fd.as_raw_fd(),
+                    request.as_ptr() as *const libc::c_void,
+                    request.len(),
+                    0,
+                )
+            };
+            if sent >= 0 {
+                break;
+            }
+            let e = std::io::Error::last_os_error();
+            if e.kind() != std::io::ErrorKind::Interrupted {
+                return Err(e.into());
+            }
+        }
+
+        // Replies to earlier requests that timed out may still be queued, so
+        // skip any with another sequence number.
+        let mut reply: Vec<u8> = vec![0; RECV_BUFFER_SIZE];
+        loop {
+            let received = unsafe {
+                libc::recv(
+                    self.fd.as_raw_fd(),
+                    reply.as_mut_ptr() as *mut libc::c_void,
+                    RECV_BUFFER_SIZE,
+                    0,
+                )
+            };
+            if received < 0 {
+                let e = std::io::Error::last_os_error();
+                // The refresh signal interrupts recv.
+                if e.kind() == std::io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return Err(e.into());
+            }
+            let received = received as usize;
+            if received >= NLMSG_HDR_LEN
+                && u32::from_ne_bytes(reply[8..12].try_into().unwrap()) == self.seq
+            {
+                reply.truncate(received);
+                break;
+            }
+        }
+
+        Ok(reply)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hand-built in the layout of the kernel's replies. The multi-byte fields
+    // are written little-endian, so these assume a little-endian machine.
+    const GETFAMILY_REPLY: [u8; 48] = [
+        48, 0, 0, 0, 0x10, 0, 0, 0, 1, 0, 0, 0, 0xd2, 0x04, 0, 0, // nlmsghdr
+        1, 2, 0, 0, // genlmsghdr
+        12, 0, 2, 0, b'n', b'l', b'8', b'0', b'2', b'1', b'1', 0, // CTRL_ATTR_FAMILY_NAME
+        6, 0, 1, 0, 0x1c, 0, 0, 0, // CTRL_ATTR_FAMILY_ID
+        8, 0, 3, 0, 1, 0, 0, 0, // CTRL_ATTR_VERSION
+    ];
+
+    const GET_INTERFACE_REPLY: [u8; 68] = [
+        68, 0, 0, 0, 0x1c, 0, 0, 0, 2, 0, 0, 0, 0xd2, 0x04, 0, 0, // nlmsghdr
+        7, 1, 0, 0, // genlmsghdr
+        8, 0, 3, 0, 3, 0, 0, 0, // NL80211_ATTR_IFINDEX
+        10, 0, 4, 0, b'w', b'l', b'a', b'n', b'0', 0, 0, 0, // NL80211_ATTR_IFNAME
+        8, 0, 1, 0, 0, 0, 0, 0, // NL80211_ATTR_WIPHY
+        8, 0, 5, 0, 2, 0, 0, 0, // NL80211_ATTR_IFTYPE
+        11, 0, 52, 0, b'H', b'o', b'm', b'e', b'N', b'e', b't', 0, // NL80211_ATTR_SSID
+    ];
+
+    // An interface that isn't connected has no NL80211_ATTR_SSID.
+    const GET_INTERFACE_DISCONNECTED_REPLY: [u8; 36] = [
+        36, 0, 0, 0, 0x1c, 0, 0, 0, 2, 0, 0, 0, 0xd2, 0x04, 0, 0, // nlmsghdr
+        7, 1, 0, 0, // genlmsghdr
+        8, 0, 3, 0, 3, 0, 0, 0, // NL80211_ATTR_IFINDEX
+        8, 0, 5, 0, 2, 0, 0, 0, // NL80211_ATTR_IFTYPE
+    ];
+
+    // -ENODEV followed by the request's nlmsghdr.
+    const ERROR_REPLY: [u8; 36] = [
+        36, 0, 0, 0, 2, 0, 0, 1, 2, 0, 0, 0, 0xd2, 0x04, 0, 0, // nlmsghdr
+        0xed, 0xff, 0xff, 0xff, // error
+        28, 0, 0, 0, 0x1c, 0, 1, 0, 2, 0, 0, 0, 0, 0, 0, 0, // request nlmsghdr
+    ];
+
+    const DONE_REPLY: [u8; 20] = [
+        20, 0, 0, 0, 3, 0, 2, 0, 2, 0, 0, 0, 0xd2, 0x04, 0, 0, // nlmsghdr
+        0, 0, 0, 0,
+    ];
+
+    #[test]
+    fn parse_family_id_reply() {
+        assert_eq!(parse_family_id(&GETFAMILY_REPLY).unwrap(), 0x1c);
+    }
+
+    #[test]
+    fn parse_reply_attrs_skips_padding() {
+        let attrs = parse_reply_attrs(&GET_INTERFACE_REPLY).unwrap();
+        let types: Vec<u16> = attrs.iter().map(|(attr_type, _)| *attr_type).collect();
+        assert_eq!(types, [3, 4, 1, 5, 52]);
+        assert_eq!(attrs[1].1, b"wlan0\0");
+    }
+
+    #[test]
+    fn parse_ssid_reply() {
+        assert_eq!(
+            parse_ssid(&GET_INTERFACE_REPLY).unwrap().as_deref(),
+            Some("HomeNet")
+        );
+        assert_eq!(parse_ssid(&GET_INTERFACE_DISCONNECTED_REPLY).unwrap(), None);
+    }
+
+    #[test]
+    fn parse_error_reply() {
+        match parse_ssid(&ERROR_REPLY) {
+            Err(Error::IO(e)) => assert_eq!(e.raw_os_error(), Some(libc::ENODEV)),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert!(parse_ssid(&ERROR_REPLY[..18]).is_err());
+    }
+
+    #[test]
+    fn parse_done_reply() {
+        assert!(parse_ssid(&DONE_REPLY).is_err());
+    }
+
+    #[test]
+    fn parse_invalid_lengths() {
+        assert!(parse_reply_attrs(&[]).is_err());
+        assert!(parse_reply_attrs(&GET_INTERFACE_REPLY[..10]).is_err());
+        // nlmsg_len is longer than what was received.
+        assert!(parse_reply_attrs(&GET_INTERFACE_REPLY[..40]).is_err());
+
+        // nla_len goes past the end of the message.
+        let mut reply = GET_INTERFACE_REPLY;
+        reply[56] = 40;
+        assert!(parse_ssid(&reply).is_err());
+
+        // nla_len is shorter than the attribute header.
+        let mut reply = GET_INTERFACE_REPLY;
+        reply[20] = 2;
+        assert!(parse_ssid(&reply).is_err());
+    }
+
+    #[test]
+    fn build_request_matches_reply_layout() {
+        let request = build_request(GENL_ID_CTRL, CTRL_CMD_GETFAMILY, 7, &[(2, b"nl80211\0")]);
+        assert_eq!(request.len(), 32);
+        assert_eq!(&request[0..4], &32u32.to_ne_bytes());
+        assert_eq!(&request[8..12], &7u32.to_ne_bytes());
+        assert_eq!(&request[16..18], &[CTRL_CMD_GETFAMILY, 1]);
+        assert_eq!(&request[20..24], &[12, 0, 2, 0]);
+    }
+}
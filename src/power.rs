@@ -0,0 +1,131 @@
+use std::path::Path;
+
+use crate::error::Error;
+use crate::swaybar_object::SwaybarObject;
+use crate::sysfs::{get_sorted_entries, read_trimmed};
+
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+const LID_DIR: &str = "/proc/acpi/button/lid";
+
+/// Returns whether an AC adapter is plugged in, or None if there are no AC
+/// adapters (such as on desktops).
+///
+/// AC adapters are power supplies of the "Mains" type, which are usually
+/// named like "AC", "ACAD", or "ADP1".
+pub fn is_ac_online() -> Option<bool> {
+    parse_ac_online(
+        get_sorted_entries(Path::new(POWER_SUPPLY_DIR), "")
+            .iter()
+            .map(|supply_dir| {
+                (
+                    read_trimmed(&supply_dir.join("type")),
+                    read_trimmed(&supply_dir.join("online")),
+                )
+            }),
+    )
+}
+
+// Combines the "type" and "online" attributes of each power supply.
+fn parse_ac_online(
+    supplies: impl Iterator<Item = (Option<String>, Option<String>)>,
+) -> Option<bool> {
+    let mut online: Option<bool> = None;
+    for (supply_type, supply_online) in supplies {
+        if supply_type.is_none_or(|supply_type| supply_type != "Mains") {
+            continue;
+        }
+        let Some(supply_online) = supply_online else {
+            continue;
+        };
+        if supply_online == "1" {
+            return Some(true);
+        }
+        online = Some(false);
+    }
+
+    online
+}
+
+/// Returns whether the lid is open, or None if there is no lid.
+pub fn is_lid_open() -> Option<bool> {
+    let state = get_sorted_entries(Path::new(LID_DIR), "")
+        .into_iter()
+        .find_map(|lid_dir| read_trimmed(&lid_dir.join("state")))?;
+
+    Some(parse_lid_state(&state))
+}
+
+// The state file is like "state:      open".
+fn parse_lid_state(state: &str) -> bool {
+    !state.ends_with("closed")
+}
+
+#[derive(Debug, Default)]
+pub struct PowerInfo;
+
+impl PowerInfo {
+    /// `ac_online` is from `is_ac_online`, which the main loop reads once
+    /// per update.
+    pub fn update(
+        &mut self,
+        ac_online: Option<bool>,
+        object: &mut SwaybarObject,
+    ) -> Result<(), Error> {
+        let mut labels: Vec<&str> = Vec::new();
+        match ac_online {
+            Some(true) => labels.push("AC"),
+            Some(false) => labels.push("BAT"),
+            None => (),
+        }
+        if is_lid_open() == Some(false) {
+            labels.push("LID closed");
+        }
+
+        if labels.is_empty() {
+            object.update_as_hidden();
+        } else {
+            object.update_as_generic(labels.join(" "), None);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn supply(supply_type: &str, online: Option<&str>) -> (Option<String>, Option<String>) {
+        (
+            Some(supply_type.to_owned()),
+            online.map(|online| online.to_owned()),
+        )
+    }
+
+    #[test]
+    fn ac_online() {
+        let supplies = [supply("Battery", Some("1")), supply("Mains", Some("1"))];
+        assert_eq!(parse_ac_online(supplies.into_iter()), Some(true));
+        let supplies = [supply("Mains", Some("0")), supply("Battery", None)];
+        assert_eq!(parse_ac_online(supplies.into_iter()), Some(false));
+        // Any of several adapters being online is enough.
+        let supplies = [supply("Mains", Some("0")), supply("Mains", Some("1"))];
+        assert_eq!(parse_ac_online(supplies.into_iter()), Some(true));
+    }
+
+    #[test]
+    fn ac_online_without_adapter() {
+        assert_eq!(parse_ac_online(std::iter::empty()), None);
+        // USB and battery supplies also have an "online" attribute.
+        let supplies = [supply("USB", Some("1")), supply("Battery", Some("1"))];
+        assert_eq!(parse_ac_online(supplies.into_iter()), None);
+        let supplies = [supply("Mains", None), (None, Some("1".to_owned()))];
+        assert_eq!(parse_ac_online(supplies.into_iter()), None);
+    }
+
+    #[test]
+    fn lid_state() {
+        assert!(parse_lid_state("state:      open"));
+        assert!(!parse_lid_state("state:      closed"));
+    }
+}
@@ -0,0 +1,125 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::asound::{self, PcmDirection};
+use crate::error::Error;
+use crate::processes::{get_process_dirs, read_comm};
+use crate::swaybar_object::SwaybarObject;
+use crate::threshold::ThresholdLevel;
+
+// Capture devices are like "/dev/snd/pcmC0D0c", where the "c" is for capture
+// and a "p" would be for playback.
+fn is_capture_device(path: &str) -> bool {
+    path.strip_prefix("/dev/snd/pcmC")
+        .and_then(|rest| rest.strip_suffix('c'))
+        .and_then(|rest| rest.split_once('D'))
+        .is_some_and(|(card, device)| {
+            [card, device]
+                .iter()
+                .all(|num| !num.is_empty() && num.bytes().all(|b| b.is_ascii_digit()))
+        })
+}
+
+// Returns whether the process has a video device (such as a webcam) and a
+// sound capture device open. The fds of other users' processes can't be
+// read, which returns an error.
+fn get_open_devices(process_dir: &Path) -> io::Result<(bool, bool)> {
+    let mut camera = false;
+    let mut mic = false;
+    for entry in fs::read_dir(process_dir.join("fd"))?.filter_map(|entry| entry.ok()) {
+        let Some(target) = fs::read_link(entry.path())
+            .ok()
+            .and_then(|target| target.to_str().map(|target| target.to_owned()))
+        else {
+            continue;
+        };
+        if target.starts_with("/dev/video") {
+            camera = true;
+        } else if is_capture_device(&target) {
+            mic = true;
+        }
+    }
+
+    Ok((camera, mic))
+}
+
+// Appends `name` to `names` if it isn't already in it.
+fn push_unique(names: &mut Vec<String>, name: String) {
+    if !names.contains(&name) {
+        names.push(name);
+    }
+}
+
+/// Shows the processes using a camera or a microphone.
+#[derive(Debug, Default)]
+pub struct PrivacyInfo;
+
+impl PrivacyInfo {
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        let mut camera_names: Vec<String> = Vec::new();
+        let mut mic_names: Vec<String> = Vec::new();
+        for process_dir in get_process_dirs()? {
+            // Skip processes of other users, and processes that have exited
+            // since listing /proc.
+            let Ok((camera, mic)) = get_open_devices(&process_dir) else {
+                continue;
+            };
+            if !camera && !mic {
+                continue;
+            }
+            let Some(name) = read_comm(&process_dir) else {
+                continue;
+            };
+            if camera {
+                push_unique(&mut camera_names, name.clone());
+            }
+            if mic {
+                push_unique(&mut mic_names, name);
+            }
+        }
+
+        // If none of the user's processes have a capture device open, the
+        // microphone may still be used by a process of another user (such as
+        // a system-wide sound server). Which process isn't known then, so the
+        // sound cards with running capture streams are shown instead.
+        if mic_names.is_empty() {
+            for substream in asound::get_running_substreams(PcmDirection::Capture) {
+                push_unique(&mut mic_names, format!("card {}", substream.card));
+            }
+        }
+
+        let mut parts: Vec<String> = Vec::new();
+        if !camera_names.is_empty() {
+            parts.push(format!("CAM {}", camera_names.join(",")));
+        }
+        if !mic_names.is_empty() {
+            parts.push(format!("MIC {}", mic_names.join(",")));
+        }
+
+        if parts.is_empty() {
+            object.update_as_hidden();
+        } else {
+            object.update_as_threshold(parts.join(" "), ThresholdLevel::Critical);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capture_devices() {
+        assert!(is_capture_device("/dev/snd/pcmC0D0c"));
+        assert!(is_capture_device("/dev/snd/pcmC12D3c"));
+        assert!(!is_capture_device("/dev/snd/pcmC0D0p"));
+        assert!(!is_capture_device("/dev/snd/pcmCD0c"));
+        assert!(!is_capture_device("/dev/snd/pcmC0Dc"));
+        assert!(!is_capture_device("/dev/snd/pcmC0D0"));
+        assert!(!is_capture_device("/dev/snd/controlC0"));
+        assert!(!is_capture_device("/dev/snd/pcmCxD0c"));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::args::Args;
 use crate::error::Error;
+use crate::graph::{Graph, GraphItemType};
 
 use std::fmt::Write as FMTWrite;
 use std::fs::File;
@@ -8,41 +9,9 @@ use std::io::prelude::*;
 
 const MAX_NET_FRESH_COUNT: u32 = 2;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub enum GraphItemType {
-    Download,
-    Upload,
-    Both,
-}
-
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub struct GraphItem {
-    value: char,
-    num_value: f64,
-    value_type: GraphItemType,
-}
-
-impl GraphItem {
-    pub fn get_value(&self) -> char {
-        self.value
-    }
-
-    pub fn set_value(&mut self, c: char) {
-        self.value = c;
-    }
-
-    pub fn get_num_value(&self) -> f64 {
-        self.num_value
-    }
-
-    pub fn get_value_type(&self) -> GraphItemType {
-        self.value_type
-    }
-}
-
 pub struct NetInfo<'a> {
     dev_name: String,
-    graph: Vec<GraphItem>,
+    graph: Graph,
     down: u64,
     prev_down: u64,
     up: u64,
@@ -50,31 +19,15 @@ pub struct NetInfo<'a> {
     first_iteration: bool,
     pub errored: bool,
     fresh_count: u32,
+    dev_counters: Vec<(String, u64, u64)>,
     args: &'a Args,
 }
 
 impl<'a> NetInfo<'a> {
     pub fn new(dev_name: String, graph_size_opt: Option<usize>, args: &'a Args) -> Self {
-        let mut s = Self {
-            dev_name,
-            graph: vec![GraphItem {
-                value: ' ',
-                num_value: 0.0,
-                value_type: GraphItemType::Both,
-            }],
-            down: 0,
-            prev_down: 0,
-            up: 0,
-            prev_up: 0,
-            first_iteration: true,
-            errored: false,
-            fresh_count: 0,
-            args,
-        };
-
-        if let Some(graph_size) = graph_size_opt {
+        let graph_size: usize = if let Some(graph_size) = graph_size_opt {
             if graph_size > 0 {
-                s.graph = s.graph.repeat(graph_size);
+                graph_size
             } else {
                 let mut stderr_handle = std::io::stderr().lock();
                 stderr_handle
@@ -83,16 +36,28 @@ impl<'a> NetInfo<'a> {
                             .as_bytes(),
                     )
                     .ok();
-                s.graph = s.graph.repeat(10);
+                10
             }
         } else {
-            s.graph = s.graph.repeat(10);
-        }
+            10
+        };
 
-        s
+        Self {
+            dev_name,
+            graph: Graph::new(graph_size, GraphItemType::Both),
+            down: 0,
+            prev_down: 0,
+            up: 0,
+            prev_up: 0,
+            first_iteration: true,
+            errored: false,
+            fresh_count: 0,
+            dev_counters: Vec::new(),
+            args,
+        }
     }
 
-    fn check_netdev_wb(&self, netdev: &str) -> bool {
+    pub fn check_netdev_wb(&self, netdev: &str) -> bool {
         if self.args.whitelist_exact.is_empty()
             && self.args.whitelist_contains.is_empty()
             && self.args.whitelist_begins.is_empty()
@@ -155,25 +120,27 @@ impl<'a> NetInfo<'a> {
             netdev_file.read_to_string(&mut netdev_string)?;
         }
 
+        self.parse_netdev(&netdev_string)
+    }
+
+    fn parse_netdev(&mut self, netdev_string: &str) -> Result<(), Error> {
+        // There may not be whitespace after the ':' if the received bytes
+        // count is large, so split the device name at the ':'.
         let mut dev_lines: Vec<&str> = Vec::new();
         if self.dev_name == "all" {
             for line in netdev_string.lines().map(|line| line.trim()) {
-                let has: bool = line
-                    .split(' ')
-                    .take(1)
-                    .filter(|first| {
-                        first.ends_with(':') && self.check_netdev_wb(first.trim_end_matches(':'))
-                    })
-                    .count()
-                    != 0;
-                if has {
+                if let Some((dev_name, _)) = line.split_once(':')
+                    && self.check_netdev_wb(dev_name.trim())
+                {
                     dev_lines.push(line);
                 }
             }
         } else {
-            let dev_str: String = self.dev_name.clone() + ":";
             for line in netdev_string.lines().map(|line| line.trim()) {
-                if line.starts_with(&dev_str) {
+                if line
+                    .split_once(':')
+                    .is_some_and(|(dev_name, _)| dev_name.trim() == self.dev_name)
+                {
                     dev_lines.push(line);
                     break;
                 }
@@ -190,15 +157,21 @@ impl<'a> NetInfo<'a> {
 
         let mut down: u64 = 0;
         let mut up: u64 = 0;
+        self.dev_counters.clear();
 
         for line in dev_lines {
-            let entries: Vec<&str> = line.split_whitespace().collect();
-            if entries.len() < 10 {
+            let (dev_name, counters) = line.split_once(':').unwrap_or((line, ""));
+            let entries: Vec<&str> = counters.split_whitespace().collect();
+            if entries.len() < 9 {
                 return Err(format!("NetInfo::update: Failed to parse /proc/net/dev, \"{}\" device line is too short", self.dev_name).into());
             }
 
-            down += entries[1].parse::<u64>()?;
-            up += entries[9].parse::<u64>()?;
+            let dev_down = entries[0].parse::<u64>()?;
+            let dev_up = entries[8].parse::<u64>()?;
+            down += dev_down;
+            up += dev_up;
+            self.dev_counters
+                .push((dev_name.trim().to_owned(), dev_down, dev_up));
         }
 
         if !self.first_iteration {
@@ -218,7 +191,7 @@ impl<'a> NetInfo<'a> {
     pub fn get_netstring(
         &mut self,
         graph_max_opt: Option<f64>,
-    ) -> Result<(String, &Vec<GraphItem>, usize, String), Error> {
+    ) -> Result<(String, &Graph, usize, String), Error> {
         let down_diff: f64 = if self.down > self.prev_down {
             let value = (self.down - self.prev_down) as f64;
             self.prev_down = self.down;
@@ -265,84 +238,10 @@ impl<'a> NetInfo<'a> {
         let mut diff_max_string = String::new();
         let mut history_max_idx = 0;
 
-        if let Some(graph_max) = graph_max_opt {
-            let graph_value: u8 = if diff_max > graph_max {
-                8
-            } else {
-                (diff_max / graph_max * 8.0f64).round() as u8
-            };
-
-            self.graph.remove(0);
-            match graph_value {
-                0 => self.graph.push(GraphItem {
-                    value: ' ',
-                    num_value: 0.0,
-                    value_type: graph_type,
-                }),
-                1 => self.graph.push(GraphItem {
-                    value: '▁',
-                    num_value: 0.0,
-                    value_type: graph_type,
-                }),
-                2 => self.graph.push(GraphItem {
-                    value: '▂',
-                    num_value: 0.0,
-                    value_type: graph_type,
-                }),
-                3 => self.graph.push(GraphItem {
-                    value: '▃',
-                    num_value: 0.0,
-                    value_type: graph_type,
-                }),
-                4 => self.graph.push(GraphItem {
-                    value: '▄',
-                    num_value: 0.0,
-                    value_type: graph_type,
-                }),
-                5 => self.graph.push(GraphItem {
-                    value: '▅',
-                    num_value: 0.0,
-                    value_type: graph_type,
-                }),
-                6 => self.graph.push(GraphItem {
-                    value: '▆',
-                    num_value: 0.0,
-                    value_type: graph_type,
-                }),
-                7 => self.graph.push(GraphItem {
-                    value: '▇',
-                    num_value: 0.0,
-                    value_type: graph_type,
-                }),
-                _ => self.graph.push(GraphItem {
-                    value: '█',
-                    num_value: 0.0,
-                    value_type: graph_type,
-                }),
-            }
-        } else {
-            self.graph.rotate_left(1);
-            {
-                let end_idx = self.graph.len() - 1;
-                self.graph[end_idx] = GraphItem {
-                    value: ' ',
-                    num_value: diff_max,
-                    value_type: graph_type,
-                };
-            }
-
-            let mut history_max: f64 = 0.0;
-            for (idx, value) in self
-                .graph
-                .iter()
-                .map(|item| item.get_num_value())
-                .enumerate()
-            {
-                if history_max < value {
-                    history_max = value;
-                    history_max_idx = idx;
-                }
-            }
+        self.graph.push(diff_max, graph_type, graph_max_opt);
+        if graph_max_opt.is_none() {
+            let history_max: f64;
+            (history_max, history_max_idx) = self.graph.get_history_max();
 
             if history_max > 1024.0 * 1024.0 {
                 write!(
@@ -355,20 +254,6 @@ impl<'a> NetInfo<'a> {
             } else {
                 write!(&mut diff_max_string, "{:.0} B", history_max)?;
             }
-
-            for item in self.graph.iter_mut() {
-                match (8.0 * item.get_num_value() / history_max).round() as u8 {
-                    0 => item.set_value(' '),
-                    1 => item.set_value('▁'),
-                    2 => item.set_value('▂'),
-                    3 => item.set_value('▃'),
-                    4 => item.set_value('▄'),
-                    5 => item.set_value('▅'),
-                    6 => item.set_value('▆'),
-                    7 => item.set_value('▇'),
-                    _ => item.set_value('█'),
-                }
-            }
         }
 
         Ok((output, &self.graph, history_max_idx, diff_max_string))
@@ -382,6 +267,12 @@ impl<'a> NetInfo<'a> {
         self.dev_name = dev_name.to_owned();
     }
 
+    /// Returns the (device name, total received bytes, total sent bytes) of
+    /// each net device read in the last update.
+    pub fn get_dev_counters(&self) -> &[(String, u64, u64)] {
+        &self.dev_counters
+    }
+
     pub fn get_fresh(&mut self) -> bool {
         if self.fresh_count < MAX_NET_FRESH_COUNT {
             self.fresh_count += 1;
@@ -396,85 +287,67 @@ impl<'a> NetInfo<'a> {
     }
 }
 
-pub fn get_meminfo() -> Result<String, Error> {
-    let mut meminfo_string = String::new();
-    {
-        let mut meminfo: File = File::open("/proc/meminfo")?;
-        meminfo.read_to_string(&mut meminfo_string)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The received bytes of eth0 are large enough that there is no space
+    // after the ':'.
+    const NETDEV: &str = "\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo:    4000      40    0    0    0     0          0         0     4000      40    0    0    0     0       0          0
+  eth0:123456789012 9000000    0    0    0     0          0         0 98765432  500000    0    0    0     0       0          0
+ wlan0:    1000      10    0    0    0     0          0         0     2000      20    0    0    0     0       0          0
+";
+
+    #[test]
+    fn parse_netdev_named_device() {
+        let args = Args::default();
+        let mut net = NetInfo::new("eth0".to_owned(), None, &args);
+        net.parse_netdev(NETDEV).unwrap();
+        assert_eq!(
+            net.get_dev_counters(),
+            [("eth0".to_owned(), 123456789012, 98765432)]
+        );
     }
 
-    let mut is_total_mega = false;
-    let mut total: u32 = 0;
-    let mut available: u32 = 0;
-    for line in meminfo_string.lines() {
-        if line.starts_with("MemTotal:") {
-            let line_parts = line
-                .split_whitespace()
-                .map(|s| s.to_owned())
-                .collect::<Vec<String>>();
-            total = line_parts[1].parse()?;
-        } else if line.starts_with("MemAvailable:") {
-            let line_parts = line
-                .split_whitespace()
-                .map(|s| s.to_owned())
-                .collect::<Vec<String>>();
-            available = line_parts[1].parse()?;
-        }
+    #[test]
+    fn parse_netdev_all_devices() {
+        let args = Args::default();
+        let mut net = NetInfo::new("all".to_owned(), None, &args);
+        net.parse_netdev(NETDEV).unwrap();
+        let names: Vec<&str> = net
+            .get_dev_counters()
+            .iter()
+            .map(|(name, _, _)| name.as_str())
+            .collect();
+        assert_eq!(names, ["lo", "eth0", "wlan0"]);
+        assert_eq!(net.prev_down, 4000 + 123456789012 + 1000);
+        assert_eq!(net.prev_up, 4000 + 98765432 + 2000);
     }
 
-    let mut used: f64 = (total - available) as f64;
-    let mut is_used_mega = false;
-
-    if total == 0 {
-        Ok("0".into())
-    } else {
-        let mut total = total as f64;
-
-        if total > 1024.0 {
-            total /= 1024.0;
-            is_total_mega = true;
-        }
-
-        if used > 1024.0 {
-            used /= 1024.0;
-            is_used_mega = true;
-        }
-
-        let mut output: String;
-        if is_used_mega {
-            output = format!("{:.2} ", used);
-            output.push_str("MiB / ");
-        } else {
-            output = format!("{:.0} ", used);
-            output.push_str("KiB / ");
-        }
-
-        if is_total_mega {
-            write!(&mut output, "{:.2} ", total)?;
-            output.push_str("MiB");
-        } else {
-            write!(&mut output, "{:.0} ", total)?;
-            output.push_str("KiB");
-        }
-
-        Ok(output)
-    }
-}
-
-pub fn get_loadavg() -> Result<String, Error> {
-    let mut loadavg_string = String::new();
-    {
-        let mut loadavg_file: File = File::open("/proc/loadavg")?;
-        loadavg_file.read_to_string(&mut loadavg_string)?;
+    #[test]
+    fn parse_netdev_all_devices_blacklist() {
+        let mut args = Args::default();
+        args.blacklist_exact.insert("lo".to_owned());
+        let mut net = NetInfo::new("all".to_owned(), None, &args);
+        net.parse_netdev(NETDEV).unwrap();
+        let names: Vec<&str> = net
+            .get_dev_counters()
+            .iter()
+            .map(|(name, _, _)| name.as_str())
+            .collect();
+        assert_eq!(names, ["eth0", "wlan0"]);
     }
 
-    let loadavg_parts: Vec<&str> = loadavg_string.split_whitespace().collect();
-    if loadavg_parts.len() < 3 {
-        return Err("loadavg: failed to parse".to_owned().into());
+    #[test]
+    fn parse_netdev_missing_device() {
+        let args = Args::default();
+        let mut net = NetInfo::new("eth1".to_owned(), None, &args);
+        assert!(net.parse_netdev(NETDEV).is_err());
+        // "eth" is only a prefix of "eth0".
+        let mut net = NetInfo::new("eth".to_owned(), None, &args);
+        assert!(net.parse_netdev(NETDEV).is_err());
     }
-
-    Ok(format!(
-        "{} {} {}",
-        loadavg_parts[0], loadavg_parts[1], loadavg_parts[2]
-    ))
 }
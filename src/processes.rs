@@ -0,0 +1,187 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::error::Error;
+use crate::swaybar_object::SwaybarObject;
+use crate::threshold::ThresholdLevel;
+
+/// Returns the directories in /proc of all processes.
+pub fn get_process_dirs() -> Result<Vec<PathBuf>, Error> {
+    Ok(fs::read_dir("/proc")?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.chars().all(|c| c.is_ascii_digit()))
+        })
+        .map(|entry| entry.path())
+        .collect())
+}
+
+/// Returns the "comm" (the executable name, up to 15 characters) of a process.
+pub fn read_comm(process_dir: &Path) -> Option<String> {
+    fs::read_to_string(process_dir.join("comm"))
+        .ok()
+        .map(|comm| comm.trim_end_matches('\n').to_owned())
+}
+
+// The arguments in cmdline are separated by null bytes, and kernel threads have
+// an empty cmdline.
+fn read_cmdline(process_dir: &Path) -> Option<String> {
+    let cmdline = fs::read(process_dir.join("cmdline")).ok()?;
+    Some(
+        String::from_utf8_lossy(&cmdline)
+            .trim_end_matches('\0')
+            .replace('\0', " "),
+    )
+}
+
+// Returns the parent process id from /proc/<pid>/stat.
+fn read_ppid(process_dir: &Path) -> Option<u32> {
+    let stat = fs::read_to_string(process_dir.join("stat")).ok()?;
+    // The comm field is in parentheses and may contain spaces, so skip past
+    // it. The state and ppid fields follow it.
+    let (_, after_comm) = stat.rsplit_once(')')?;
+    after_comm.split_whitespace().nth(1)?.parse().ok()
+}
+
+// Returns the dirs of the ancestors of swaybar_info, such as the shell that
+// started it, whose command lines may contain the regexes.
+fn get_ancestor_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    let mut pid: u32 = std::process::id();
+    while pid > 1 {
+        let Some(ppid) = read_ppid(Path::new(&format!("/proc/{}", pid))) else {
+            break;
+        };
+        if ppid > 1 {
+            dirs.push(PathBuf::from(format!("/proc/{}", ppid)));
+        }
+        pid = ppid;
+    }
+
+    dirs
+}
+
+struct WatchedProcess {
+    label: String,
+    regex: Regex,
+    /// If true, the label is shown when the process is not running.
+    expected: bool,
+}
+
+// Parses "<label>[SPLIT]<regex>", printing a warning if it is invalid.
+fn parse_watch(watch_str: &str, expected: bool) -> Option<WatchedProcess> {
+    let result: Result<WatchedProcess, String> = watch_str
+        .split_once("[SPLIT]")
+        .ok_or_else(|| "missing [SPLIT]".to_owned())
+        .and_then(|(label, regex_str)| {
+            Ok(WatchedProcess {
+                label: label.to_owned(),
+                regex: Regex::new(regex_str).map_err(|e| e.to_string())?,
+                expected,
+            })
+        });
+
+    match result {
+        Ok(watched) => Some(watched),
+        Err(e) => {
+            let mut stderr_handle = io::stderr().lock();
+            stderr_handle
+                .write_all(
+                    format!(
+                        "WARNING: Invalid process watch \"{}\" ({}), ignoring...\n",
+                        watch_str, e
+                    )
+                    .as_bytes(),
+                )
+                .ok();
+            None
+        }
+    }
+}
+
+pub struct ProcessesInfo {
+    watched: Vec<WatchedProcess>,
+    own_dir: PathBuf,
+    ancestor_dirs: Vec<PathBuf>,
+}
+
+impl ProcessesInfo {
+    /// `watches` and `expects` are "<label>[SPLIT]<regex>", where the regex is
+    /// matched against the name and the command line of each process.
+    ///
+    /// The label of a watch is shown while a matching process is running, and
+    /// "NO <label>" is shown (as a warning) for an expect while none are
+    /// running.
+    pub fn new(watches: &[String], expects: &[String]) -> Self {
+        Self {
+            watched: watches
+                .iter()
+                .filter_map(|watch_str| parse_watch(watch_str, false))
+                .chain(
+                    expects
+                        .iter()
+                        .filter_map(|expect_str| parse_watch(expect_str, true)),
+                )
+                .collect(),
+            own_dir: PathBuf::from(format!("/proc/{}", std::process::id())),
+            ancestor_dirs: get_ancestor_dirs(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.watched.is_empty()
+    }
+
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        let mut is_running: Vec<bool> = vec![false; self.watched.len()];
+        for process_dir in get_process_dirs()? {
+            if is_running.iter().all(|running| *running) {
+                break;
+            } else if process_dir == self.own_dir {
+                continue;
+            }
+            // The process may have exited since listing /proc.
+            let Some(comm) = read_comm(&process_dir) else {
+                continue;
+            };
+            // Ancestors (such as sway) are only matched by name, as their
+            // command lines may contain the regexes.
+            let cmdline = if self.ancestor_dirs.contains(&process_dir) {
+                String::new()
+            } else {
+                read_cmdline(&process_dir).unwrap_or_default()
+            };
+            for (watched, running) in self.watched.iter().zip(is_running.iter_mut()) {
+                if !*running && (watched.regex.is_match(&comm) || watched.regex.is_match(&cmdline))
+                {
+                    *running = true;
+                }
+            }
+        }
+
+        let mut labels: Vec<String> = Vec::new();
+        let mut level = ThresholdLevel::Normal;
+        for (watched, running) in self.watched.iter().zip(is_running) {
+            if watched.expected && !running {
+                labels.push(format!("NO {}", watched.label));
+                level = ThresholdLevel::Warning;
+            } else if !watched.expected && running {
+                labels.push(watched.label.to_owned());
+            }
+        }
+
+        if labels.is_empty() {
+            object.update_as_hidden();
+        } else {
+            object.update_as_threshold(labels.join(" "), level);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt::Write;
+use std::fs;
+use std::io;
+
+use crate::error::Error;
+use crate::swaybar_object::SwaybarObject;
+use crate::threshold::{ThresholdLevel, Thresholds};
+
+const PRESSURE_DIR: &str = "/proc/pressure";
+const DEFAULT_RESOURCES: [&str; 3] = ["cpu", "memory", "io"];
+const DEFAULT_THRESHOLDS: Thresholds = Thresholds {
+    warning: 10.0,
+    critical: 40.0,
+};
+const DEFAULT_SUSTAIN_COUNT: u32 = 3;
+
+/// The "avg10" values of a /proc/pressure file, in percent.
+#[derive(Debug, Copy, Clone, Default)]
+struct Pressure {
+    some: f64,
+    full: Option<f64>,
+}
+
+// Returns None if the kernel doesn't support PSI (or it is disabled).
+fn read_pressure(resource: &str) -> Result<Option<Pressure>, Error> {
+    let string = match fs::read_to_string(format!("{}/{}", PRESSURE_DIR, resource)) {
+        Ok(string) => string,
+        Err(e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::NotFound | io::ErrorKind::Unsupported
+            ) =>
+        {
+            return Ok(None);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut pressure: Option<Pressure> = None;
+    for line in string.lines() {
+        let mut parts = line.split_whitespace();
+        let kind = parts.next();
+        let Some(avg10) = parts
+            .find_map(|part| part.strip_prefix("avg10="))
+            .and_then(|avg10| avg10.parse::<f64>().ok())
+        else {
+            continue;
+        };
+        match kind {
+            Some("some") => pressure.get_or_insert_default().some = avg10,
+            Some("full") => pressure.get_or_insert_default().full = Some(avg10),
+            _ => (),
+        }
+    }
+
+    pressure
+        .map(Some)
+        .ok_or_else(|| format!("psi: failed to parse {}/{}", PRESSURE_DIR, resource).into())
+}
+
+pub struct PsiInfo {
+    resources: Vec<String>,
+    thresholds: Thresholds,
+    sustain_count: u32,
+    warning_count: u32,
+    critical_count: u32,
+}
+
+impl PsiInfo {
+    /// `resources` is a comma separated list of "cpu", "memory", and "io". If
+    /// empty, all of them are shown.
+    ///
+    /// The block is only colored once the highest "some" value stays at or
+    /// above a threshold for `sustain_count` updates in a row.
+    pub fn new(
+        resources: &str,
+        thresholds: Option<Thresholds>,
+        sustain_count: Option<u32>,
+    ) -> Self {
+        let mut resources: Vec<String> = resources
+            .split(',')
+            .map(|s| s.trim().to_owned())
+            .filter(|s| !s.is_empty())
+            .collect();
+        if resources.is_empty() {
+            resources = DEFAULT_RESOURCES.iter().map(|s| s.to_string()).collect();
+        }
+
+        Self {
+            resources,
+            thresholds: thresholds.unwrap_or(DEFAULT_THRESHOLDS),
+            sustain_count: sustain_count.unwrap_or(DEFAULT_SUSTAIN_COUNT).max(1),
+            warning_count: 0,
+            critical_count: 0,
+        }
+    }
+
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        let mut text = String::from("PSI");
+        let mut max_some: f64 = 0.0;
+        for resource in &self.resources {
+            let Some(pressure) = read_pressure(resource)? else {
+                object.update_as_hidden();
+                return Ok(());
+            };
+            let label: &str = match resource.as_str() {
+                "memory" => "mem",
+                other => other,
+            };
+            write!(&mut text, " {} {:.1}", label, pressure.some)?;
+            // "full" is always zero for cpu at the system level.
+            if let Some(full) = pressure.full
+                && resource != "cpu"
+            {
+                write!(&mut text, "/{:.1}", full)?;
+            }
+            max_some = max_some.max(pressure.some);
+        }
+
+        match self.thresholds.get_level(max_some) {
+            ThresholdLevel::Critical => {
+                self.warning_count += 1;
+                self.critical_count += 1;
+            }
+            ThresholdLevel::Warning => {
+                self.warning_count += 1;
+                self.critical_count = 0;
+            }
+            ThresholdLevel::Normal => {
+                self.warning_count = 0;
+                self.critical_count = 0;
+            }
+        }
+
+        let level = if self.critical_count >= self.sustain_count {
+            ThresholdLevel::Critical
+        } else if self.warning_count >= self.sustain_count {
+            ThresholdLevel::Warning
+        } else {
+            ThresholdLevel::Normal
+        };
+        object.update_as_threshold(text, level);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,261 @@
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::swaybar_object::SwaybarObject;
+use crate::sysfs::{get_sorted_entries, read_trimmed};
+use crate::threshold::ThresholdLevel;
+
+const RFKILL_SYSFS_DIR: &str = "/sys/class/rfkill";
+const RFKILL_DEV_PATH: &str = "/dev/rfkill";
+
+// From linux/rfkill.h.
+const RFKILL_OP_CHANGE_ALL: u8 = 3;
+
+// The sysfs type names, their labels, and their types in linux/rfkill.h.
+const RADIO_TYPES: [(&str, &str, u8); 3] = [
+    ("wlan", "WIFI", 1),
+    ("bluetooth", "BT", 2),
+    ("wwan", "WWAN", 5),
+];
+const BLUETOOTH_IDX: usize = 1;
+
+// Some drivers only update the sysfs state after the write has returned, so
+// the block is updated again after this delay.
+const TOGGLE_REFRESH_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+struct RadioState {
+    present: bool,
+    soft_blocked: bool,
+    hard_blocked: bool,
+}
+
+impl RadioState {
+    fn is_blocked(&self) -> bool {
+        self.soft_blocked || self.hard_blocked
+    }
+}
+
+// A radio type may have more than one device, such as a platform switch and
+// the wireless device itself, and is blocked if any of them are.
+fn read_radio_states() -> [RadioState; 3] {
+    parse_radio_states(
+        get_sorted_entries(Path::new(RFKILL_SYSFS_DIR), "rfkill")
+            .iter()
+            .filter_map(|rfkill_dir| {
+                Some((
+                    read_trimmed(&rfkill_dir.join("type"))?,
+                    read_trimmed(&rfkill_dir.join("soft")),
+                    read_trimmed(&rfkill_dir.join("hard")),
+                ))
+            }),
+    )
+}
+
+// Combines the "type", "soft", and "hard" attributes of each rfkill device.
+fn parse_radio_states(
+    devices: impl Iterator<Item = (String, Option<String>, Option<String>)>,
+) -> [RadioState; 3] {
+    let mut states = [RadioState::default(); 3];
+    for (radio_type, soft, hard) in devices {
+        let Some(idx) = RADIO_TYPES
+            .iter()
+            .position(|(type_name, _, _)| *type_name == radio_type)
+        else {
+            continue;
+        };
+        let state = &mut states[idx];
+        state.present = true;
+        if soft.is_some_and(|soft| soft != "0") {
+            state.soft_blocked = true;
+        }
+        if hard.is_some_and(|hard| hard != "0") {
+            state.hard_blocked = true;
+        }
+    }
+
+    states
+}
+
+// Sets the soft block of all devices of a type with a struct rfkill_event.
+fn write_change_all(radio_type: u8, blocked: bool) -> Result<(), Error> {
+    // idx (u32), type, op, soft, and hard.
+    let mut event = [0u8; 8];
+    event[4] = radio_type;
+    event[5] = RFKILL_OP_CHANGE_ALL;
+    event[6] = blocked as u8;
+
+    let mut rfkill_file = OpenOptions::new().write(true).open(RFKILL_DEV_PATH)?;
+    rfkill_file.write_all(&event)?;
+
+    Ok(())
+}
+
+// Unparks the main thread after TOGGLE_REFRESH_DELAY so that the block is
+// updated once the new state shows up in sysfs.
+fn schedule_refresh() {
+    let main_thread = thread::current();
+    thread::spawn(move || {
+        thread::sleep(TOGGLE_REFRESH_DELAY);
+        main_thread.unpark();
+    });
+}
+
+fn warn_toggle_failed(result: Result<(), Error>) {
+    if let Err(e) = result {
+        let mut stderr_handle = io::stderr().lock();
+        stderr_handle
+            .write_all(
+                format!(
+                    "WARNING: rfkill: Failed to write to {}: {}\n",
+                    RFKILL_DEV_PATH, e
+                )
+                .as_bytes(),
+            )
+            .ok();
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RfkillInfo;
+
+impl RfkillInfo {
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        let states = read_radio_states();
+        let present: Vec<(&str, RadioState)> = RADIO_TYPES
+            .iter()
+            .zip(states)
+            .filter(|(_, state)| state.present)
+            .map(|((_, label, _), state)| (*label, state))
+            .collect();
+
+        if present.is_empty() {
+            object.update_as_hidden();
+        } else if present.iter().all(|(_, state)| state.is_blocked()) {
+            object.update_as_generic("AIRPLANE".to_owned(), None);
+        } else {
+            let text: Vec<String> = present
+                .iter()
+                .map(|(label, state)| {
+                    if state.hard_blocked {
+                        format!("{} hw-off", label)
+                    } else if state.soft_blocked {
+                        format!("{} off", label)
+                    } else {
+                        format!("{} on", label)
+                    }
+                })
+                .collect();
+            let level = if present.iter().any(|(_, state)| state.hard_blocked) {
+                ThresholdLevel::Warning
+            } else {
+                ThresholdLevel::Normal
+            };
+            object.update_as_threshold(text.join(" "), level);
+        }
+
+        Ok(())
+    }
+
+    /// Blocks the shown radios if any are unblocked, and unblocks them
+    /// otherwise. Other radio types (such as NFC) aren't changed, as they
+    /// aren't shown.
+    ///
+    /// The block is updated right after this, and again once the new state
+    /// should have shown up in sysfs.
+    pub fn toggle_airplane_mode(&self) {
+        let states = read_radio_states();
+        let any_unblocked = states
+            .iter()
+            .any(|state| state.present && !state.is_blocked());
+        // Stop at the first failure, as it is usually a permission error.
+        warn_toggle_failed(
+            RADIO_TYPES
+                .iter()
+                .zip(states)
+                .filter(|(_, state)| state.present)
+                .try_for_each(|((_, _, radio_type), _)| {
+                    write_change_all(*radio_type, any_unblocked)
+                }),
+        );
+        schedule_refresh();
+    }
+
+    pub fn toggle_bluetooth(&self) {
+        let state = read_radio_states()[BLUETOOTH_IDX];
+        if state.present {
+            let (_, _, radio_type) = RADIO_TYPES[BLUETOOTH_IDX];
+            warn_toggle_failed(write_change_all(radio_type, !state.soft_blocked));
+            schedule_refresh();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(
+        radio_type: &str,
+        soft: &str,
+        hard: &str,
+    ) -> (String, Option<String>, Option<String>) {
+        (
+            radio_type.to_owned(),
+            Some(soft.to_owned()),
+            Some(hard.to_owned()),
+        )
+    }
+
+    #[test]
+    fn radio_states() {
+        let states = parse_radio_states(
+            [
+                device("wlan", "0", "0"),
+                device("bluetooth", "1", "0"),
+                device("nfc", "1", "1"),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(
+            states,
+            [
+                RadioState {
+                    present: true,
+                    soft_blocked: false,
+                    hard_blocked: false
+                },
+                RadioState {
+                    present: true,
+                    soft_blocked: true,
+                    hard_blocked: false
+                },
+                RadioState::default(),
+            ]
+        );
+    }
+
+    #[test]
+    fn radio_states_blocked_by_any_device() {
+        // A platform switch that is hard blocked and the device itself.
+        let states =
+            parse_radio_states([device("wlan", "0", "1"), device("wlan", "0", "0")].into_iter());
+        assert!(states[0].present && states[0].hard_blocked && states[0].is_blocked());
+        assert!(!states[0].soft_blocked);
+    }
+
+    #[test]
+    fn radio_states_missing_attributes() {
+        let states = parse_radio_states([("wwan".to_owned(), None, None)].into_iter());
+        assert!(states[2].present && !states[2].is_blocked());
+        assert!(
+            parse_radio_states(std::iter::empty())
+                .iter()
+                .all(|state| !state.present)
+        );
+    }
+}
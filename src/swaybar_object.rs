@@ -8,6 +8,8 @@ use std::{
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::threshold::ThresholdLevel;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SwaybarHeader {
     pub version: u32,
@@ -163,11 +165,24 @@ impl SwaybarObject {
         self.color = color;
     }
 
+    pub fn update_as_threshold(&mut self, metric: String, level: ThresholdLevel) {
+        self.full_text = metric;
+        self.color = level.get_color();
+        self.urgent = if level.is_urgent() { Some(true) } else { None };
+    }
+
     pub fn update_as_error(&mut self, msg: String) {
         self.full_text = msg;
         self.color = Some("#ff2222ff".to_owned());
     }
 
+    /// swaybar doesn't draw blocks with an empty `full_text`.
+    pub fn update_as_hidden(&mut self) {
+        self.full_text = String::new();
+        self.color = None;
+        self.urgent = None;
+    }
+
     #[allow(dead_code)]
     pub fn set_name(&mut self, name: Option<String>) {
         self.name = name;
@@ -0,0 +1,24 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Reads a file, such as a sysfs attribute, with surrounding whitespace
+/// removed.
+pub fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_owned())
+}
+
+/// Returns the paths of entries in `dir` beginning with `prefix`, sorted.
+pub fn get_sorted_entries(dir: &Path, prefix: &str) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default();
+    entries.sort();
+
+    entries
+}
@@ -0,0 +1,205 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+use crate::swaybar_object::SwaybarObject;
+use crate::sysfs::{get_sorted_entries, read_trimmed};
+use crate::threshold::{ThresholdLevel, Thresholds};
+
+pub const HWMON_DIR: &str = "/sys/class/hwmon";
+const THERMAL_DIR: &str = "/sys/class/thermal";
+
+// Used as the warning threshold when a sensor only reports a critical
+// temperature.
+const CRIT_WARNING_OFFSET: f64 = 15.0;
+
+#[derive(Debug, Clone)]
+pub struct TempSensor {
+    /// The name of the hwmon device, or the type of the thermal zone.
+    chip: String,
+    label: String,
+    input_path: PathBuf,
+    thresholds: Option<Thresholds>,
+}
+
+// sysfs temperatures are in millidegrees Celsius.
+fn read_millidegrees(path: &Path) -> Option<f64> {
+    read_trimmed(path)?
+        .parse::<i64>()
+        .ok()
+        .map(|value| value as f64 / 1000.0)
+}
+
+fn get_sensor_thresholds(max: Option<f64>, crit: Option<f64>) -> Option<Thresholds> {
+    let crit = crit.filter(|crit| *crit > 0.0)?;
+    let warning = max
+        .filter(|max| *max > 0.0 && *max < crit)
+        .unwrap_or(crit - CRIT_WARNING_OFFSET);
+
+    Some(Thresholds {
+        warning,
+        critical: crit,
+    })
+}
+
+/// Finds all temperature sensors from hwmon devices and thermal zones.
+pub fn discover_sensors() -> Vec<TempSensor> {
+    let mut sensors: Vec<TempSensor> = Vec::new();
+
+    for hwmon_dir in get_sorted_entries(Path::new(HWMON_DIR), "hwmon") {
+        let chip = read_trimmed(&hwmon_dir.join("name")).unwrap_or_else(|| {
+            hwmon_dir
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        for input_path in get_sorted_entries(&hwmon_dir, "temp") {
+            let Some(prefix) = input_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix("_input"))
+                .map(|prefix| prefix.to_owned())
+            else {
+                continue;
+            };
+            let label = read_trimmed(&hwmon_dir.join(format!("{}_label", prefix)))
+                .unwrap_or_else(|| format!("{} {}", chip, prefix));
+            let thresholds = get_sensor_thresholds(
+                read_millidegrees(&hwmon_dir.join(format!("{}_max", prefix))),
+                read_millidegrees(&hwmon_dir.join(format!("{}_crit", prefix))),
+            );
+            sensors.push(TempSensor {
+                chip: chip.clone(),
+                label,
+                input_path,
+                thresholds,
+            });
+        }
+    }
+
+    for zone_dir in get_sorted_entries(Path::new(THERMAL_DIR), "thermal_zone") {
+        let Some(zone_type) = read_trimmed(&zone_dir.join("type")) else {
+            continue;
+        };
+
+        let mut crit: Option<f64> = None;
+        for trip_idx in 0.. {
+            let Some(trip_type) =
+                read_trimmed(&zone_dir.join(format!("trip_point_{}_type", trip_idx)))
+            else {
+                break;
+            };
+            if trip_type == "critical" {
+                crit = read_millidegrees(&zone_dir.join(format!("trip_point_{}_temp", trip_idx)));
+                break;
+            }
+        }
+
+        sensors.push(TempSensor {
+            chip: zone_type.clone(),
+            label: zone_type,
+            input_path: zone_dir.join("temp"),
+            thresholds: get_sensor_thresholds(None, crit),
+        });
+    }
+
+    sensors
+}
+
+impl TempSensor {
+    pub fn matches(&self, pattern: &str) -> bool {
+        self.label == pattern || self.chip == pattern
+    }
+
+    /// Returns the temperature in degrees Celsius.
+    pub fn read(&self) -> Option<f64> {
+        read_millidegrees(&self.input_path)
+    }
+
+    pub fn get_label(&self) -> &str {
+        &self.label
+    }
+}
+
+pub struct ThermalInfo {
+    sensors: Vec<TempSensor>,
+    patterns: Vec<String>,
+    thresholds: Option<Thresholds>,
+    show_label: bool,
+    temp: Option<f64>,
+}
+
+impl ThermalInfo {
+    /// `patterns` is a comma separated list of sensor labels (or hwmon device
+    /// names/thermal zone types) to use. If empty, all sensors are used.
+    pub fn new(patterns: &str, thresholds: Option<Thresholds>, show_label: bool) -> Self {
+        Self {
+            sensors: Vec::new(),
+            patterns: patterns
+                .split(',')
+                .map(|s| s.trim().to_owned())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            thresholds,
+            show_label,
+            temp: None,
+        }
+    }
+
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        if self.sensors.is_empty() {
+            self.sensors = discover_sensors()
+                .into_iter()
+                .filter(|sensor| {
+                    self.patterns.is_empty()
+                        || self.patterns.iter().any(|pattern| sensor.matches(pattern))
+                })
+                .collect();
+            if self.sensors.is_empty() {
+                return Err("thermal: no matching temperature sensors found"
+                    .to_owned()
+                    .into());
+            }
+        }
+
+        let mut max: Option<(f64, &TempSensor)> = None;
+        let mut level = ThresholdLevel::Normal;
+        for sensor in &self.sensors {
+            let Some(temp) = sensor.read() else {
+                continue;
+            };
+            if max.is_none_or(|(max_temp, _)| temp > max_temp) {
+                max = Some((temp, sensor));
+            }
+
+            // Use the worst level out of all sensors, as each sensor may have
+            // its own thresholds.
+            if let Some(thresholds) = self.thresholds.or(sensor.thresholds) {
+                let sensor_level = thresholds.get_level(temp);
+                if sensor_level > level {
+                    level = sensor_level;
+                }
+            }
+        }
+
+        let Some((max_temp, max_sensor)) = max else {
+            return Err("thermal: failed to read temperature sensors"
+                .to_owned()
+                .into());
+        };
+
+        let text: String = if self.show_label {
+            format!("{} {:.0}°C", max_sensor.get_label(), max_temp)
+        } else {
+            format!("{:.0}°C", max_temp)
+        };
+        object.update_as_threshold(text, level);
+        self.temp = Some(max_temp);
+
+        Ok(())
+    }
+
+    /// Returns the maximum temperature from the last successful update.
+    pub fn get_temp(&self) -> Option<f64> {
+        self.temp
+    }
+}
@@ -0,0 +1,132 @@
+use std::io::{self, Write};
+
+use crate::args::Args;
+
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub enum ThresholdLevel {
+    Normal,
+    Warning,
+    Critical,
+}
+
+impl ThresholdLevel {
+    pub fn get_color(self) -> Option<String> {
+        match self {
+            ThresholdLevel::Normal => None,
+            ThresholdLevel::Warning => Some("#ffff88ff".into()),
+            ThresholdLevel::Critical => Some("#ff8888ff".into()),
+        }
+    }
+
+    pub fn is_urgent(self) -> bool {
+        self == ThresholdLevel::Critical
+    }
+}
+
+/// Warning and critical thresholds, where a value at or above a threshold is
+/// at that level.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Thresholds {
+    pub warning: f64,
+    pub critical: f64,
+}
+
+impl Thresholds {
+    /// Parses "<warning>,<critical>", where the warning threshold can't be
+    /// above the critical threshold.
+    pub fn parse(string: &str) -> Option<Self> {
+        let (warning, critical) = string.split_once(',')?;
+        let thresholds = Self {
+            warning: warning.trim().parse().ok()?,
+            critical: critical.trim().parse().ok()?,
+        };
+        // Also rejects NaN.
+        if thresholds.warning <= thresholds.critical {
+            Some(thresholds)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_level(&self, value: f64) -> ThresholdLevel {
+        if value >= self.critical {
+            ThresholdLevel::Critical
+        } else if value >= self.warning {
+            ThresholdLevel::Warning
+        } else {
+            ThresholdLevel::Normal
+        }
+    }
+}
+
+/// Gets thresholds from the arg with the given key, printing a warning if it
+/// is invalid.
+pub fn get_thresholds_arg(args: &Args, key: &str) -> Option<Thresholds> {
+    let string = args.map.get(key)?;
+    let thresholds = Thresholds::parse(string);
+    if thresholds.is_none() {
+        let mut stderr_handle = io::stderr().lock();
+        stderr_handle
+            .write_all(
+                format!(
+                    "WARNING: Invalid value passed to --{}=<warning>,<critical>, ignoring...\n",
+                    key
+                )
+                .as_bytes(),
+            )
+            .ok();
+    }
+
+    thresholds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_thresholds() {
+        assert_eq!(
+            Thresholds::parse("70,90"),
+            Some(Thresholds {
+                warning: 70.0,
+                critical: 90.0
+            })
+        );
+        assert_eq!(
+            Thresholds::parse(" 0.8 , 1.5 "),
+            Some(Thresholds {
+                warning: 0.8,
+                critical: 1.5
+            })
+        );
+        assert_eq!(
+            Thresholds::parse("50,50"),
+            Some(Thresholds {
+                warning: 50.0,
+                critical: 50.0
+            })
+        );
+    }
+
+    #[test]
+    fn parse_thresholds_invalid() {
+        assert_eq!(Thresholds::parse(""), None);
+        assert_eq!(Thresholds::parse("70"), None);
+        assert_eq!(Thresholds::parse("70,"), None);
+        assert_eq!(Thresholds::parse(",90"), None);
+        assert_eq!(Thresholds::parse("70,90,95"), None);
+        assert_eq!(Thresholds::parse("seventy,90"), None);
+        assert_eq!(Thresholds::parse("90,70"), None);
+        assert_eq!(Thresholds::parse("NaN,90"), None);
+    }
+
+    #[test]
+    fn get_level_at_thresholds() {
+        let thresholds = Thresholds::parse("70,90").unwrap();
+        assert_eq!(thresholds.get_level(69.9), ThresholdLevel::Normal);
+        assert_eq!(thresholds.get_level(70.0), ThresholdLevel::Warning);
+        assert_eq!(thresholds.get_level(89.9), ThresholdLevel::Warning);
+        assert_eq!(thresholds.get_level(90.0), ThresholdLevel::Critical);
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cpu;
+use crate::error::Error;
+use crate::processes::{get_process_dirs, read_comm};
+use crate::swaybar_object::SwaybarObject;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TopMode {
+    Cpu,
+    Memory,
+}
+
+impl TopMode {
+    pub fn parse(string: &str) -> Option<Self> {
+        match string {
+            "cpu" => Some(TopMode::Cpu),
+            "mem" | "memory" => Some(TopMode::Memory),
+            _ => None,
+        }
+    }
+}
+
+// Returns the name and the user + system time (in USER_HZ) of a process from
+// /proc/<pid>/stat.
+fn read_process_times(process_dir: &Path) -> Option<(String, u64)> {
+    let stat = fs::read_to_string(process_dir.join("stat")).ok()?;
+    // The name is in parentheses and may contain spaces or parentheses.
+    let (front, after_comm) = stat.rsplit_once(')')?;
+    let (_, name) = front.split_once('(')?;
+    // "utime" and "stime" are the 14th and 15th fields, and the fields after
+    // the name start from the 3rd.
+    let mut fields = after_comm.split_whitespace().skip(11);
+    let utime: u64 = fields.next()?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+
+    Some((name.to_owned(), utime + stime))
+}
+
+// Returns the resident set size (in pages) of a process from
+// /proc/<pid>/statm.
+fn read_process_rss(process_dir: &Path) -> Option<u64> {
+    fs::read_to_string(process_dir.join("statm"))
+        .ok()?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()
+}
+
+pub struct TopInfo {
+    mode: TopMode,
+    prev_times: HashMap<PathBuf, u64>,
+    prev_total: u64,
+}
+
+impl TopInfo {
+    pub fn new(mode: TopMode) -> Self {
+        Self {
+            mode,
+            prev_times: HashMap::new(),
+            prev_total: 0,
+        }
+    }
+
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        let top: Option<(String, f64)> = match self.mode {
+            TopMode::Cpu => self.get_top_cpu()?,
+            TopMode::Memory => get_top_memory()?,
+        };
+
+        if let Some((name, percent)) = top {
+            object.update_as_generic(format!("{} {:.0}%", name, percent), None);
+        } else {
+            object.update_as_hidden();
+        }
+
+        Ok(())
+    }
+
+    // The percentage is of a single cpu core, like in top.
+    fn get_top_cpu(&mut self) -> Result<Option<(String, f64)>, Error> {
+        let (all, cores) = cpu::read_stat()?;
+        let total: u64 = all.total();
+        let total_diff = total.saturating_sub(self.prev_total);
+
+        let mut times: HashMap<PathBuf, u64> = HashMap::new();
+        let mut top: Option<(String, u64)> = None;
+        for process_dir in get_process_dirs()? {
+            // The process may have exited since listing /proc.
+            let Some((name, time)) = read_process_times(&process_dir) else {
+                continue;
+            };
+            if let Some(prev_time) = self.prev_times.get(&process_dir) {
+                let diff = time.saturating_sub(*prev_time);
+                if top.as_ref().is_none_or(|(_, top_diff)| diff > *top_diff) {
+                    top = Some((name, diff));
+                }
+            }
+            times.insert(process_dir, time);
+        }
+
+        // Nothing is shown on the first update, as there is nothing to
+        // compare to.
+        let is_first = self.prev_total == 0;
+        self.prev_times = times;
+        self.prev_total = total;
+        if is_first || total_diff == 0 {
+            return Ok(None);
+        }
+
+        Ok(top.map(|(name, diff)| {
+            let core_count = cores.len().max(1) as f64;
+            (name, diff as f64 / total_diff as f64 * core_count * 100.0)
+        }))
+    }
+}
+
+fn get_top_memory() -> Result<Option<(String, f64)>, Error> {
+    let total_pages = unsafe { libc::sysconf(libc::_SC_PHYS_PAGES) };
+    if total_pages <= 0 {
+        return Err("top: failed to get the number of physical pages"
+            .to_owned()
+            .into());
+    }
+
+    let mut top: Option<(&Path, u64)> = None;
+    let process_dirs = get_process_dirs()?;
+    for process_dir in &process_dirs {
+        let Some(rss) = read_process_rss(process_dir) else {
+            continue;
+        };
+        if top.is_none_or(|(_, top_rss)| rss > top_rss) {
+            top = Some((process_dir, rss));
+        }
+    }
+
+    let Some((process_dir, rss)) = top else {
+        return Ok(None);
+    };
+    let name: String = read_comm(process_dir).unwrap_or_default();
+
+    Ok(Some((name, rss as f64 / total_pages as f64 * 100.0)))
+}
@@ -0,0 +1,87 @@
+const KIB: f64 = 1024.0;
+const MIB: f64 = 1024.0 * 1024.0;
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+const TIB: f64 = 1024.0 * 1024.0 * 1024.0 * 1024.0;
+
+/// Formats bytes as B, KiB, MiB, GiB, or TiB, whichever is the largest unit
+/// that the value reaches.
+pub fn format_bytes(bytes: f64) -> String {
+    if bytes >= TIB {
+        format!("{:.2} TiB", bytes / TIB)
+    } else if bytes >= GIB {
+        format!("{:.2} GiB", bytes / GIB)
+    } else if bytes >= MIB {
+        format!("{:.2} MiB", bytes / MIB)
+    } else if bytes >= KIB {
+        format!("{:.2} KiB", bytes / KIB)
+    } else {
+        format!("{:.0} B", bytes)
+    }
+}
+
+/// Parses a byte count with an optional "K", "M", "G", or "T" suffix (which
+/// may also be written as "KiB", "MiB", etc.).
+pub fn parse_bytes(string: &str) -> Option<u64> {
+    let string = string.trim();
+    let number_end = string
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(string.len());
+    let (number, suffix) = string.split_at(number_end);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: f64 = match suffix.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1.0,
+        "K" | "KIB" => KIB,
+        "M" | "MIB" => MIB,
+        "G" | "GIB" => GIB,
+        "T" | "TIB" => TIB,
+        _ => return None,
+    };
+
+    Some((number * multiplier) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_bytes_suffixes() {
+        assert_eq!(parse_bytes("512"), Some(512));
+        assert_eq!(parse_bytes("512B"), Some(512));
+        assert_eq!(parse_bytes("2K"), Some(2048));
+        assert_eq!(parse_bytes("2kib"), Some(2048));
+        assert_eq!(parse_bytes("3M"), Some(3 * 1024 * 1024));
+        assert_eq!(parse_bytes(" 50 GiB "), Some(50 * 1024 * 1024 * 1024));
+        assert_eq!(parse_bytes("1T"), Some(1024 * 1024 * 1024 * 1024));
+    }
+
+    #[test]
+    fn parse_bytes_fractions() {
+        assert_eq!(parse_bytes("1.5K"), Some(1536));
+        assert_eq!(parse_bytes("0.5G"), Some(512 * 1024 * 1024));
+        // Fractions of a byte are truncated.
+        assert_eq!(parse_bytes("1.9"), Some(1));
+    }
+
+    #[test]
+    fn parse_bytes_invalid() {
+        assert_eq!(parse_bytes(""), None);
+        assert_eq!(parse_bytes("G"), None);
+        assert_eq!(parse_bytes("."), None);
+        assert_eq!(parse_bytes("1.2.3"), None);
+        assert_eq!(parse_bytes("-5K"), None);
+        assert_eq!(parse_bytes("5X"), None);
+        assert_eq!(parse_bytes("5KB"), None);
+    }
+
+    #[test]
+    fn format_bytes_units() {
+        assert_eq!(format_bytes(0.0), "0 B");
+        assert_eq!(format_bytes(1023.0), "1023 B");
+        assert_eq!(format_bytes(1024.0), "1.00 KiB");
+        assert_eq!(format_bytes(1536.0), "1.50 KiB");
+        assert_eq!(format_bytes(MIB * 2.25), "2.25 MiB");
+        assert_eq!(format_bytes(GIB), "1.00 GiB");
+        assert_eq!(format_bytes(TIB * 1024.0), "1024.00 TiB");
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::swaybar_object::SwaybarObject;
+use crate::units::format_bytes;
+
+const STATE_FILE_NAME: &str = "net_usage.json";
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_QUOTA_URGENT_PERCENT: f64 = 90.0;
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+struct DeviceUsage {
+    day_down: u64,
+    day_up: u64,
+    month_down: u64,
+    month_up: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct UsageState {
+    day: String,
+    month: String,
+    devices: HashMap<String, DeviceUsage>,
+}
+
+pub struct DataUsage {
+    state: UsageState,
+    prev_counters: HashMap<String, (u64, u64)>,
+    path: Option<PathBuf>,
+    quota: Option<u64>,
+    quota_urgent_percent: f64,
+    skip_virtual: bool,
+    last_save: Instant,
+}
+
+fn is_physical_dev(dev_name: &str) -> bool {
+    Path::new("/sys/class/net")
+        .join(dev_name)
+        .join("device")
+        .exists()
+}
+
+fn get_state_file_path() -> Option<PathBuf> {
+    let state_dir: PathBuf = if let Some(dir) = std::env::var_os("XDG_STATE_HOME")
+        && !dir.is_empty()
+    {
+        PathBuf::from(dir)
+    } else {
+        let mut dir = PathBuf::from(std::env::var_os("HOME")?);
+        dir.push(".local");
+        dir.push("state");
+        dir
+    };
+
+    Some(state_dir.join("swaybar_info").join(STATE_FILE_NAME))
+}
+
+impl DataUsage {
+    /// If `skip_virtual` is true, then devices without a
+    /// `/sys/class/net/<device>/device` link (such as bridges, VPNs, and
+    /// veths) are not counted, as their traffic also goes through a physical
+    /// device.
+    pub fn new(quota: Option<u64>, quota_urgent_percent: Option<f64>, skip_virtual: bool) -> Self {
+        let path = get_state_file_path();
+        let mut state: UsageState = Default::default();
+
+        if let Some(path) = path.as_ref() {
+            match fs::read_to_string(path) {
+                Ok(string) => match serde_json::from_str(&string) {
+                    Ok(loaded) => state = loaded,
+                    Err(e) => {
+                        let mut stderr_handle = io::stderr().lock();
+                        stderr_handle
+                            .write_all(
+                                format!(
+                                    "WARNING: Failed to parse \"{}\", ignoring: {}\n",
+                                    path.display(),
+                                    e
+                                )
+                                .as_bytes(),
+                            )
+                            .ok();
+                    }
+                },
+                Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+                Err(e) => {
+                    let mut stderr_handle = io::stderr().lock();
+                    stderr_handle
+                        .write_all(
+                            format!(
+                                "WARNING: Failed to read \"{}\", ignoring: {}\n",
+                                path.display(),
+                                e
+                            )
+                            .as_bytes(),
+                        )
+                        .ok();
+                }
+            }
+        }
+
+        Self {
+            state,
+            prev_counters: HashMap::new(),
+            path,
+            quota,
+            quota_urgent_percent: quota_urgent_percent.unwrap_or(DEFAULT_QUOTA_URGENT_PERCENT),
+            skip_virtual,
+            last_save: Instant::now(),
+        }
+    }
+
+    /// `dev_counters` is (device name, total received bytes, total sent
+    /// bytes) for each net device, as returned by `NetInfo::get_dev_counters`.
+    pub fn update(&mut self, dev_counters: &[(String, u64, u64)], object: &mut SwaybarObject) {
+        let now: DateTime<Local> = Local::now();
+        let day: String = now.format("%F").to_string();
+        let month: String = now.format("%Y-%m").to_string();
+
+        if self.state.month != month {
+            self.state.month = month;
+            for usage in self.state.devices.values_mut() {
+                usage.month_down = 0;
+                usage.month_up = 0;
+            }
+        }
+        if self.state.day != day {
+            self.state.day = day;
+            for usage in self.state.devices.values_mut() {
+                usage.day_down = 0;
+                usage.day_up = 0;
+            }
+        }
+
+        let mut day_total: u64 = 0;
+        let mut month_total: u64 = 0;
+
+        for (dev_name, down, up) in dev_counters {
+            if self.skip_virtual && !is_physical_dev(dev_name) {
+                continue;
+            }
+
+            // The first time a device is seen, its counters include traffic
+            // from before swaybar_info started, which was already accounted
+            // for in the state file (or was never tracked), so only the
+            // counters are stored.
+            if let Some((prev_down, prev_up)) =
+                self.prev_counters.insert(dev_name.to_owned(), (*down, *up))
+            {
+                // Counters go backwards if the device was re-created.
+                let down_diff = if *down >= prev_down {
+                    down - prev_down
+                } else {
+                    *down
+                };
+                let up_diff = if *up >= prev_up { up - prev_up } else { *up };
+
+                let usage = self.state.devices.entry(dev_name.to_owned()).or_default();
+                usage.day_down += down_diff;
+                usage.day_up += up_diff;
+                usage.month_down += down_diff;
+                usage.month_up += up_diff;
+            }
+
+            if let Some(usage) = self.state.devices.get(dev_name) {
+                day_total += usage.day_down + usage.day_up;
+                month_total += usage.month_down + usage.month_up;
+            }
+        }
+
+        let mut text: String = format!(
+            "today {} month {}",
+            format_bytes(day_total as f64),
+            format_bytes(month_total as f64)
+        );
+        let mut color: Option<String> = None;
+        object.urgent = None;
+
+        if let Some(quota) = self.quota
+            && quota > 0
+        {
+            let percent: f64 = month_total as f64 / quota as f64 * 100.0;
+            text = format!(
+                "today {} month {} / {} ({:.0}%)",
+                format_bytes(day_total as f64),
+                format_bytes(month_total as f64),
+                format_bytes(quota as f64),
+                percent
+            );
+            if percent >= self.quota_urgent_percent {
+                color = Some("#ff8888ff".into());
+                object.urgent = Some(true);
+            }
+        }
+
+        object.update_as_generic(text, color);
+
+        // Keep counting if saving fails, as it is tried again later.
+        if self.last_save.elapsed() >= SAVE_INTERVAL
+            && let Err(e) = self.save()
+        {
+            let mut stderr_handle = io::stderr().lock();
+            stderr_handle
+                .write_all(format!("WARNING: Failed to save net usage: {}\n", e).as_bytes())
+                .ok();
+        }
+    }
+
+    pub fn save(&mut self) -> Result<(), Error> {
+        self.last_save = Instant::now();
+
+        let Some(path) = self.path.as_ref() else {
+            return Err("DataUsage: failed to get state file path (HOME not set?)"
+                .to_owned()
+                .into());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let string = serde_json::to_string(&self.state)
+            .map_err(|e| Error::from(format!("DataUsage: failed to serialize: {}", e)))?;
+
+        // Write to a temporary file first so the state file is never left
+        // partially written.
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, string)?;
+        fs::rename(&temp_path, path)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use crate::args::Args;
+use crate::error::Error;
+use crate::proc::NetInfo;
+use crate::swaybar_object::SwaybarObject;
+use crate::sysfs::read_trimmed;
+
+const DEFAULT_VPN_PATTERNS: [&str; 3] = ["wg*", "tun*", "tailscale*"];
+const DEFAULT_WARNING_COLOR: &str = "#ff8888ff";
+const IFF_UP: u32 = 0x1;
+
+pub struct VpnInfo<'a> {
+    patterns: Vec<String>,
+    expected: bool,
+    warning_color: String,
+    show_traffic: bool,
+    traffic: Option<NetInfo<'a>>,
+    args: &'a Args,
+}
+
+impl<'a> VpnInfo<'a> {
+    /// An empty `patterns` string uses the default patterns. A pattern ending
+    /// with '*' matches net devices beginning with the pattern, otherwise the
+    /// pattern must match the net device exactly.
+    pub fn new(patterns: &str, args: &'a Args) -> Self {
+        let patterns: Vec<String> = if patterns.is_empty() {
+            DEFAULT_VPN_PATTERNS
+                .iter()
+                .map(|s| (*s).to_owned())
+                .collect()
+        } else {
+            patterns.split(',').map(|s| s.trim().to_owned()).collect()
+        };
+
+        Self {
+            patterns,
+            expected: args.map.contains_key("vpn-expected"),
+            warning_color: args
+                .map
+                .get("vpn-warning-color")
+                .cloned()
+                .unwrap_or_else(|| DEFAULT_WARNING_COLOR.to_owned()),
+            show_traffic: args.map.contains_key("vpn-traffic"),
+            traffic: None,
+            args,
+        }
+    }
+
+    fn matches(&self, dev_name: &str) -> bool {
+        self.patterns.iter().any(|pattern| {
+            if let Some(prefix) = pattern.strip_suffix('*') {
+                dev_name.starts_with(prefix)
+            } else {
+                dev_name == pattern
+            }
+        })
+    }
+
+    pub fn update(&mut self, object: &mut SwaybarObject) -> Result<(), Error> {
+        let mut dev_names: Vec<String> = fs::read_dir("/sys/class/net")?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| self.matches(name) && is_dev_up(name))
+            .collect();
+        dev_names.sort();
+
+        if dev_names.is_empty() {
+            self.update_as_down(object);
+            return Ok(());
+        }
+
+        let mut text: String = dev_names.join(" ");
+
+        if self.show_traffic {
+            if self
+                .traffic
+                .as_ref()
+                .is_none_or(|traffic| traffic.get_dev_name() != dev_names[0])
+            {
+                self.traffic = Some(NetInfo::new(dev_names[0].clone(), None, self.args));
+            }
+            let traffic = self.traffic.as_mut().unwrap();
+            // The tunnel may have gone away since the devices were listed.
+            let Ok((netinfo_string, _, _, _)) =
+                traffic.update().and_then(|()| traffic.get_netstring(None))
+            else {
+                self.update_as_down(object);
+                return Ok(());
+            };
+            let netinfo_parts: Vec<&str> = netinfo_string.split_whitespace().collect();
+            write!(
+                &mut text,
+                " {} {} / {} {}",
+                netinfo_parts[0], netinfo_parts[1], netinfo_parts[2], netinfo_parts[3]
+            )?;
+        }
+
+        object.update_as_generic(text, None);
+
+        Ok(())
+    }
+
+    fn update_as_down(&mut self, object: &mut SwaybarObject) {
+        self.traffic = None;
+        if self.expected {
+            object.update_as_generic("VPN DOWN".to_owned(), Some(self.warning_color.clone()));
+        } else {
+            object.update_as_hidden();
+        }
+    }
+}
+
+fn is_dev_up(dev_name: &str) -> bool {
+    // Tunnel devices usually have an operstate of "unknown", so check the
+    // IFF_UP flag instead.
+    read_trimmed(&Path::new("/sys/class/net").join(dev_name).join("flags"))
+        .and_then(|flags| u32::from_str_radix(flags.trim_start_matches("0x"), 16).ok())
+        .is_some_and(|flags| flags & IFF_UP != 0)
+}
@@ -0,0 +1,214 @@
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::process::Command;
+
+use crate::args;
+use crate::color::get_red_green_gradient;
+use crate::error::Error;
+use crate::nl80211::Nl80211;
+use crate::swaybar_object::SwaybarObject;
+
+// Most drivers report link quality out of 70.
+const MAX_LINK_QUALITY: f32 = 70.0;
+
+struct WirelessStats {
+    dev_name: String,
+    link: f32,
+    level: f32,
+}
+
+pub struct WirelessInfo {
+    dev_name: Option<String>,
+    ssid_cmd: Option<(String, Vec<String>)>,
+    nl80211: Option<Nl80211>,
+    ssid_warned: bool,
+}
+
+impl WirelessInfo {
+    /// If `dev_name` is `None`, then the active net device is used if it is
+    /// wireless, otherwise the first wireless device is used.
+    ///
+    /// `ssid_cmd` is in the same format as `--regex-cmd` without the regex,
+    /// and its first line of output is used as the SSID instead of querying
+    /// nl80211.
+    pub fn new(dev_name: Option<String>, ssid_cmd: Option<&str>) -> Self {
+        let ssid_cmd = ssid_cmd.map(args::parse_cmd);
+
+        Self {
+            dev_name,
+            ssid_cmd,
+            nl80211: None,
+            ssid_warned: false,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        active_dev_name: Option<&str>,
+        object: &mut SwaybarObject,
+    ) -> Result<(), Error> {
+        let mut wireless_string = String::new();
+        {
+            let mut wireless_file: File = File::open("/proc/net/wireless")?;
+            wireless_file.read_to_string(&mut wireless_string)?;
+        }
+        let all_stats = parse_wireless(&wireless_string)?;
+
+        let stats: Option<&WirelessStats> = if let Some(dev_name) = self.dev_name.as_ref() {
+            all_stats.iter().find(|stats| &stats.dev_name == dev_name)
+        } else {
+            active_dev_name
+                .and_then(|active| all_stats.iter().find(|stats| stats.dev_name == active))
+                .or(all_stats.first())
+        };
+
+        let Some(stats) = stats else {
+            object.update_as_generic("no wifi".to_owned(), Some("#ff8888ff".into()));
+            return Ok(());
+        };
+
+        if stats.link <= 0.0 {
+            object.update_as_generic(
+                format!("{}: disconnected", stats.dev_name),
+                Some("#ff8888ff".into()),
+            );
+            return Ok(());
+        }
+
+        let percentage: f32 = (stats.link / MAX_LINK_QUALITY).clamp(0.0, 1.0);
+        let ssid: String = self
+            .get_ssid(&stats.dev_name)
+            .unwrap_or_else(|| stats.dev_name.clone());
+
+        object.update_as_generic(
+            format!("{} {:.0}% {:.0} dBm", ssid, percentage * 100.0, stats.level),
+            Some(get_red_green_gradient(percentage)),
+        );
+
+        Ok(())
+    }
+
+    fn get_ssid(&mut self, dev_name: &str) -> Option<String> {
+        let result: Result<Option<String>, Error> = if let Some((cmd, args)) = &self.ssid_cmd {
+            get_ssid_from_cmd(cmd, args)
+        } else {
+            if self.nl80211.is_none() {
+                match Nl80211::new() {
+                    Ok(nl80211) => self.nl80211 = Some(nl80211),
+                    Err(e) => {
+                        self.set_ssid_error(e);
+                        return None;
+                    }
+                }
+            }
+            let result = self.nl80211.as_mut().unwrap().get_ssid(dev_name);
+            // Reopen the socket on the next try in case it is broken.
+            if result.is_err() {
+                self.nl80211 = None;
+            }
+            result
+        };
+
+        match result {
+            Ok(ssid) => ssid.filter(|ssid| !ssid.is_empty()),
+            Err(e) => {
+                self.set_ssid_error(e);
+                None
+            }
+        }
+    }
+
+    // The SSID is tried again on the next update, but only warn once, as the
+    // wireless block is still shown without it.
+    fn set_ssid_error(&mut self, e: Error) {
+        if self.ssid_warned {
+            return;
+        }
+        self.ssid_warned = true;
+        let mut stderr_handle = io::stderr().lock();
+        stderr_handle
+            .write_all(format!("WARNING: wireless: Failed to get SSID: {}\n", e).as_bytes())
+            .ok();
+    }
+}
+
+fn get_ssid_from_cmd(cmd: &str, args: &[String]) -> Result<Option<String>, Error> {
+    let output = Command::new(cmd).args(args).output()?;
+    let stdout_output: String = String::from_utf8(output.stdout)?;
+
+    Ok(stdout_output
+        .lines()
+        .next()
+        .map(|line| line.trim().to_owned()))
+}
+
+fn parse_wireless(wireless_string: &str) -> Result<Vec<WirelessStats>, Error> {
+    let mut all_stats: Vec<WirelessStats> = Vec::new();
+
+    // The first two lines are headers.
+    for line in wireless_string.lines().skip(2) {
+        let entries: Vec<&str> = line.split_whitespace().collect();
+        if entries.len() < 4 || !entries[0].ends_with(':') {
+            return Err("wireless: failed to parse /proc/net/wireless"
+                .to_owned()
+                .into());
+        }
+
+        // Values may have a trailing '.' when they were updated.
+        let link: f32 = entries[2]
+            .trim_end_matches('.')
+            .parse()
+            .map_err(|_| Error::from("wireless: failed to parse link quality".to_owned()))?;
+        let level: f32 = entries[3]
+            .trim_end_matches('.')
+            .parse()
+            .map_err(|_| Error::from("wireless: failed to parse signal level".to_owned()))?;
+
+        all_stats.push(WirelessStats {
+            dev_name: entries[0].trim_end_matches(':').to_owned(),
+            link,
+            level,
+        });
+    }
+
+    Ok(all_stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIRELESS: &str = "\
+Inter-| sta-|   Quality        |   Discarded packets               | Missed | WE
+ face | tus | link level noise |  nwid  crypt   frag  retry   misc | beacon | 22
+wlan0: 0000   54.  -56.  -256        0      0      0      0     12        0
+wlan1: 0000    0   -110  -256        0      0      0      0      0        0
+";
+
+    #[test]
+    fn parse_wireless_devices() {
+        let all_stats = parse_wireless(WIRELESS).unwrap();
+        assert_eq!(all_stats.len(), 2);
+        assert_eq!(all_stats[0].dev_name, "wlan0");
+        assert_eq!(all_stats[0].link, 54.0);
+        assert_eq!(all_stats[0].level, -56.0);
+        assert_eq!(all_stats[1].dev_name, "wlan1");
+        assert_eq!(all_stats[1].link, 0.0);
+        assert_eq!(all_stats[1].level, -110.0);
+    }
+
+    #[test]
+    fn parse_wireless_headers_only() {
+        assert!(parse_wireless("").unwrap().is_empty());
+        let headers: String = WIRELESS.lines().take(2).collect::<Vec<_>>().join("\n");
+        assert!(parse_wireless(&headers).unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_wireless_malformed() {
+        let header = "Inter-| sta-|   Quality\n face | tus | link level noise\n";
+        assert!(parse_wireless(&format!("{}wlan0: 0000 54.\n", header)).is_err());
+        assert!(parse_wireless(&format!("{}wlan0 0000 54. -56.\n", header)).is_err());
+        assert!(parse_wireless(&format!("{}wlan0: 0000 good -56.\n", header)).is_err());
+    }
+}